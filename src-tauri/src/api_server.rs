@@ -0,0 +1,446 @@
+use anyhow::{Context, Result};
+use axum::extract::multipart::{Field, MultipartError};
+use axum::extract::{DefaultBodyLimit, Multipart, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::{Json, Router};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::oneshot;
+use tracing::{info, warn};
+
+use crate::auth::{generate_token, token_matches};
+use crate::pipeline::subtitles::{generate_srt, generate_vtt};
+use crate::whisper_rs_imp::transcriber::{is_supported_language, TranscriptionSettings};
+use crate::{transcribe_file_advanced_impl, TranscribeOptions};
+
+/// Default port (same as the OpenAI-compatible whisper.cpp server)
+pub const DEFAULT_API_PORT: u16 = 8178;
+
+/// Largest upload accepted by the endpoint (same limit as OpenAI's API),
+/// uploads are streamed to disk so this is not held in memory
+const MAX_UPLOAD_BYTES: usize = 25 * 1024 * 1024;
+
+/// Room for the other multipart fields on top of the file
+const MAX_FIELDS_BYTES: usize = 64 * 1024;
+
+/// Info about the running API server, returned to the frontend
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiServerInfo {
+    pub port: u16,
+    pub token: String,
+    pub base_url: String,
+    pub default_model: String,
+}
+
+struct RunningServer {
+    info: ApiServerInfo,
+    shutdown: oneshot::Sender<()>,
+}
+
+// Only one API server can run at a time
+static API_SERVER: Lazy<Mutex<Option<RunningServer>>> = Lazy::new(|| Mutex::new(None));
+
+// Unique names for uploaded files
+static UPLOAD_COUNTER: AtomicU64 = AtomicU64::new(1);
+
+#[derive(Clone)]
+struct ApiState {
+    app: AppHandle,
+    token: String,
+    default_model: String,
+}
+
+/// OpenAI-style error response: `{"error": {"message": ..., "type": ...}}`
+struct ApiError {
+    status: StatusCode,
+    message: String,
+}
+
+impl ApiError {
+    fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let error_type = if self.status.is_server_error() {
+            "server_error"
+        } else {
+            "invalid_request_error"
+        };
+
+        let body = serde_json::json!({
+            "error": {
+                "message": self.message,
+                "type": error_type,
+            }
+        });
+
+        (self.status, Json(body)).into_response()
+    }
+}
+
+fn is_authorized(headers: &HeaderMap, token: &str) -> bool {
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(|provided| token_matches(provided.trim(), token))
+        .unwrap_or(false)
+}
+
+/// Map an OpenAI model name to a local Whisper model
+/// "whisper-1" (what most clients send) uses the server's default model
+fn resolve_model_name(requested: Option<String>, default_model: &str) -> String {
+    match requested {
+        Some(model) if !model.is_empty() && model != "whisper-1" => model,
+        _ => default_model.to_string(),
+    }
+}
+
+/// Uploaded file saved for ffmpeg, deleted when dropped
+struct UploadedFile {
+    path: PathBuf,
+}
+
+impl Drop for UploadedFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Fields of the multipart request (subset of OpenAI's parameters)
+#[derive(Default)]
+struct TranscriptionRequest {
+    file: Option<UploadedFile>,
+    model: Option<String>,
+    language: Option<String>,
+    prompt: Option<String>,
+    response_format: Option<String>,
+    temperature: Option<f32>,
+}
+
+fn multipart_error(error: MultipartError) -> ApiError {
+    ApiError::new(error.status(), error.body_text())
+}
+
+fn internal_error(error: impl std::fmt::Display) -> ApiError {
+    ApiError::new(StatusCode::INTERNAL_SERVER_ERROR, error.to_string())
+}
+
+/// Stream the "file" field to `upload_dir` (keeping the extension as a format hint for ffmpeg)
+async fn save_upload(mut field: Field<'_>, upload_dir: &Path) -> Result<UploadedFile, ApiError> {
+    let extension = field
+        .file_name()
+        .and_then(|name| Path::new(name).extension())
+        .and_then(|ext| ext.to_str())
+        .filter(|ext| ext.chars().all(|c| c.is_ascii_alphanumeric()))
+        .unwrap_or("bin")
+        .to_string();
+
+    let upload = UploadedFile {
+        path: upload_dir.join(format!(
+            "upload-{}.{}",
+            UPLOAD_COUNTER.fetch_add(1, Ordering::Relaxed),
+            extension
+        )),
+    };
+    let mut file = tokio::fs::File::create(&upload.path)
+        .await
+        .map_err(internal_error)?;
+
+    let mut size = 0;
+    while let Some(chunk) = field.chunk().await.map_err(multipart_error)? {
+        size += chunk.len();
+        if size > MAX_UPLOAD_BYTES {
+            return Err(ApiError::new(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                format!(
+                    "File is too large (max {} MB)",
+                    MAX_UPLOAD_BYTES / (1024 * 1024)
+                ),
+            ));
+        }
+        file.write_all(&chunk).await.map_err(internal_error)?;
+    }
+    file.flush().await.map_err(internal_error)?;
+
+    Ok(upload)
+}
+
+async fn read_multipart(
+    mut multipart: Multipart,
+    upload_dir: &Path,
+) -> Result<TranscriptionRequest, ApiError> {
+    let mut request = TranscriptionRequest::default();
+
+    while let Some(field) = multipart.next_field().await.map_err(multipart_error)? {
+        let name = field.name().unwrap_or_default().to_string();
+
+        if name == "file" {
+            request.file = Some(save_upload(field, upload_dir).await?);
+            continue;
+        }
+
+        let value = field.text().await.map_err(multipart_error)?;
+
+        match name.as_str() {
+            "model" => request.model = Some(value),
+            "language" => request.language = Some(value),
+            "prompt" => request.prompt = Some(value),
+            "response_format" => request.response_format = Some(value),
+            "temperature" => {
+                let temperature = value.trim().parse::<f32>().map_err(|_| {
+                    ApiError::new(StatusCode::BAD_REQUEST, "temperature must be a number")
+                })?;
+                request.temperature = Some(temperature);
+            }
+            _ => {} // Ignore unsupported parameters (timestamp_granularities, ...)
+        }
+    }
+
+    Ok(request)
+}
+
+/// `POST /v1/audio/transcriptions` - OpenAI Whisper compatible endpoint
+async fn transcriptions_handler(
+    State(state): State<ApiState>,
+    headers: HeaderMap,
+    multipart: Multipart,
+) -> Result<Response, ApiError> {
+    if !is_authorized(&headers, &state.token) {
+        return Err(ApiError::new(
+            StatusCode::UNAUTHORIZED,
+            "Missing or invalid bearer token",
+        ));
+    }
+
+    let upload_dir = state
+        .app
+        .path()
+        .app_data_dir()
+        .map_err(internal_error)?
+        .join("api_uploads");
+    fs::create_dir_all(&upload_dir).map_err(internal_error)?;
+
+    let request = read_multipart(multipart, &upload_dir).await?;
+
+    let response_format = request
+        .response_format
+        .clone()
+        .unwrap_or_else(|| "json".to_string());
+    if !["json", "verbose_json", "text", "srt", "vtt"].contains(&response_format.as_str()) {
+        return Err(ApiError::new(
+            StatusCode::BAD_REQUEST,
+            format!("Unsupported response_format: '{}'", response_format),
+        ));
+    }
+
+    let upload = request
+        .file
+        .ok_or_else(|| ApiError::new(StatusCode::BAD_REQUEST, "Missing 'file' field"))?;
+
+    // ISO-639-1 code (e.g. "fr"), auto-detected when omitted
+    let language = request
+        .language
+        .map(|language| language.trim().to_lowercase())
+        .filter(|language| !language.is_empty() && language != "auto");
+    if let Some(language) = language.as_deref().filter(|l| !is_supported_language(l)) {
+        return Err(ApiError::new(
+            StatusCode::BAD_REQUEST,
+            format!("Unsupported language: '{}'", language),
+        ));
+    }
+
+    let defaults = TranscriptionSettings::default();
+    let settings = TranscriptionSettings {
+        initial_prompt: request.prompt,
+        temperature: request.temperature.unwrap_or(defaults.temperature),
+        language,
+        ..defaults
+    };
+
+    let model_name = resolve_model_name(request.model, &state.default_model);
+    info!(
+        "API transcription request (model: {}, format: {})",
+        model_name, response_format
+    );
+
+    // Uploads are deleted afterwards, so the result isn't kept in the history
+    let options = TranscribeOptions {
+        settings: Some(settings),
+        save_to_history: false,
+        ..TranscribeOptions::default()
+    };
+    let result = transcribe_file_advanced_impl(
+        state.app.clone(),
+        upload.path.to_string_lossy().to_string(),
+        Some(model_name),
+        options,
+    )
+    .await;
+
+    drop(upload);
+
+    let result = result.map_err(|e| {
+        warn!("API transcription failed: {:#}", e);
+        ApiError::new(StatusCode::INTERNAL_SERVER_ERROR, format!("{:#}", e))
+    })?;
+
+    let response = match response_format.as_str() {
+        "text" => result.text.into_response(),
+        "srt" => generate_srt(&result.segments).into_response(),
+        "vtt" => generate_vtt(&result.segments).into_response(),
+        "verbose_json" => {
+            let duration = result.segments.last().map(|s| s.end_time).unwrap_or(0.0);
+            let segments: Vec<serde_json::Value> = result
+                .segments
+                .iter()
+                .map(|segment| {
+                    // OpenAI reports log-probabilities, we track plain probabilities
+                    let avg_logprob = segment
+                        .avg_probability
+                        .map(|p| p.max(f32::MIN_POSITIVE).ln());
+
+                    serde_json::json!({
+                        "id": segment.index,
+                        "start": segment.start_time,
+                        "end": segment.end_time,
+                        "text": segment.text,
+                        "avg_logprob": avg_logprob,
+                        "no_speech_prob": segment.no_speech_probability,
+                        "temperature": segment.temperature,
+                    })
+                })
+                .collect();
+
+            Json(serde_json::json!({
+                "task": "transcribe",
+                "language": result.language,
+                "duration": duration,
+                "text": result.text,
+                "segments": segments,
+            }))
+            .into_response()
+        }
+        _ => Json(serde_json::json!({ "text": result.text })).into_response(),
+    };
+
+    Ok(response)
+}
+
+/// Start the local API server on 127.0.0.1:`port` (0 = pick a free port)
+pub async fn start_api_server(
+    app: AppHandle,
+    port: u16,
+    token: Option<String>,
+    default_model: String,
+) -> Result<ApiServerInfo> {
+    if API_SERVER
+        .lock()
+        .map_err(|e| anyhow::anyhow!("Failed to lock API server state: {}", e))?
+        .is_some()
+    {
+        anyhow::bail!("API server is already running");
+    }
+
+    let token = match token.filter(|token| !token.trim().is_empty()) {
+        Some(token) => token,
+        None => generate_token()?,
+    };
+
+    let state = ApiState {
+        app,
+        token: token.clone(),
+        default_model: default_model.clone(),
+    };
+
+    let router = Router::new()
+        .route("/v1/audio/transcriptions", post(transcriptions_handler))
+        .layer(DefaultBodyLimit::max(MAX_UPLOAD_BYTES + MAX_FIELDS_BYTES))
+        .with_state(state);
+
+    // Localhost only - never expose the models to the network
+    let listener = tokio::net::TcpListener::bind(("127.0.0.1", port))
+        .await
+        .with_context(|| format!("Failed to bind API server to port {}", port))?;
+    let bound_port = listener
+        .local_addr()
+        .context("Failed to read API server address")?
+        .port();
+
+    let info = ApiServerInfo {
+        port: bound_port,
+        token,
+        base_url: format!("http://127.0.0.1:{}/v1", bound_port),
+        default_model,
+    };
+
+    // Checked again now that the port is bound: a concurrent start may have won the
+    // race, the lock is held until this server is registered so only one is spawned
+    let mut running = API_SERVER
+        .lock()
+        .map_err(|e| anyhow::anyhow!("Failed to lock API server state: {}", e))?;
+    if running.is_some() {
+        anyhow::bail!("API server is already running");
+    }
+
+    let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+
+    tauri::async_runtime::spawn(async move {
+        let server = axum::serve(listener, router).with_graceful_shutdown(async {
+            let _ = shutdown_rx.await;
+        });
+
+        if let Err(e) = server.await {
+            warn!("API server stopped with error: {}", e);
+        }
+    });
+
+    *running = Some(RunningServer {
+        info: info.clone(),
+        shutdown: shutdown_tx,
+    });
+    drop(running);
+
+    info!("API server listening on {}", info.base_url);
+    Ok(info)
+}
+
+/// Stop the local API server (no-op if it isn't running)
+pub fn stop_api_server() -> Result<bool> {
+    let running = API_SERVER
+        .lock()
+        .map_err(|e| anyhow::anyhow!("Failed to lock API server state: {}", e))?
+        .take();
+
+    match running {
+        Some(server) => {
+            let _ = server.shutdown.send(());
+            info!("API server on port {} stopped", server.info.port);
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+/// Get info about the running API server, if any
+pub fn api_server_status() -> Result<Option<ApiServerInfo>> {
+    let server = API_SERVER
+        .lock()
+        .map_err(|e| anyhow::anyhow!("Failed to lock API server state: {}", e))?;
+
+    Ok(server.as_ref().map(|running| running.info.clone()))
+}
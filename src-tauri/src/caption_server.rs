@@ -0,0 +1,362 @@
+use anyhow::{Context, Result};
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Query, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{Html, IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::{broadcast, watch};
+use tracing::{debug, info, warn};
+
+use crate::auth::{generate_token, token_matches};
+
+/// Default port for the caption server
+pub const DEFAULT_CAPTION_PORT: u16 = 8179;
+
+/// Session id used for Whisper live chunks (they aren't session-based)
+pub const WHISPER_LIVE_SESSION_ID: &str = "whisper-live";
+
+/// Captions buffered per client before slow clients start skipping messages
+const CAPTION_CHANNEL_CAPACITY: usize = 64;
+
+/// Minimal OBS browser source: connects to /ws (passing on its own `token`
+/// query parameter) and renders the latest caption
+const BROWSER_SOURCE_HTML: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>Whisperer live captions</title>
+<style>
+  html, body { margin: 0; height: 100%; background: transparent; overflow: hidden; }
+  #captions {
+    position: absolute; left: 5%; right: 5%; bottom: 5%;
+    font: 600 42px/1.3 system-ui, sans-serif; text-align: center;
+    color: #fff; text-shadow: 0 0 6px #000, 0 0 2px #000;
+  }
+  #final { display: block; }
+  #partial { display: block; opacity: 0.7; }
+</style>
+</head>
+<body>
+<div id="captions"><span id="final"></span><span id="partial"></span></div>
+<script>
+  const finalLine = document.getElementById("final");
+  const partialLine = document.getElementById("partial");
+
+  const token = new URLSearchParams(location.search).get("token") ?? "";
+
+  function connect() {
+    const socket = new WebSocket(`ws://${location.host}/ws?token=${encodeURIComponent(token)}`);
+    socket.onmessage = (event) => {
+      const caption = JSON.parse(event.data);
+      if (caption.type === "final") {
+        finalLine.textContent = caption.translated_text ?? caption.text;
+        partialLine.textContent = "";
+      } else {
+        partialLine.textContent = caption.text;
+      }
+    };
+    socket.onclose = () => setTimeout(connect, 1000);
+  }
+
+  connect();
+</script>
+</body>
+</html>
+"#;
+
+/// Caption message broadcast to WebSocket clients
+#[derive(Debug, Clone, Serialize)]
+pub struct CaptionMessage {
+    #[serde(rename = "type")]
+    pub kind: String, // "partial" or "final"
+    pub session_id: String,
+    pub text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub translated_text: Option<String>,
+    pub timestamp_ms: u64,
+}
+
+/// Info about the running caption server, returned to the frontend
+#[derive(Debug, Clone, Serialize)]
+pub struct CaptionServerInfo {
+    pub port: u16,
+    pub token: String, // required by /ws, already included in both URLs
+    pub browser_source_url: String,
+    pub websocket_url: String,
+    pub published_session_id: Option<String>, // None = every live session
+}
+
+struct RunningCaptionServer {
+    port: u16,
+    token: String,
+    shutdown: watch::Sender<bool>, // also closes upgraded WebSocket connections
+}
+
+// Only one caption server can run at a time
+static CAPTION_SERVER: Lazy<Mutex<Option<RunningCaptionServer>>> = Lazy::new(|| Mutex::new(None));
+
+// Session whose captions are published (None = all sessions)
+static PUBLISHED_SESSION: Lazy<Mutex<Option<String>>> = Lazy::new(|| Mutex::new(None));
+
+// Fan-out channel from live sessions to every connected WebSocket client
+static CAPTION_CHANNEL: Lazy<broadcast::Sender<CaptionMessage>> =
+    Lazy::new(|| broadcast::channel(CAPTION_CHANNEL_CAPACITY).0);
+
+/// Publish a live transcription result to caption clients.
+///
+/// Cheap no-op when nobody is listening or when `session_id` isn't the
+/// published session, so live commands can call it unconditionally.
+pub fn publish_caption(session_id: &str, text: &str, is_partial: bool) {
+    publish_translated_caption(session_id, text, None, is_partial);
+}
+
+/// Same as `publish_caption`, with the translation of `text` when the session
+/// has a target language (the browser source then shows the translation)
+pub fn publish_translated_caption(
+    session_id: &str,
+    text: &str,
+    translated_text: Option<&str>,
+    is_partial: bool,
+) {
+    if text.trim().is_empty() || CAPTION_CHANNEL.receiver_count() == 0 {
+        return;
+    }
+
+    let is_published = match PUBLISHED_SESSION.lock() {
+        Ok(published) => match published.as_deref() {
+            Some(id) => id == session_id,
+            None => true,
+        },
+        Err(_) => false,
+    };
+    if !is_published {
+        return;
+    }
+
+    let timestamp_ms = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+
+    let _ = CAPTION_CHANNEL.send(CaptionMessage {
+        kind: if is_partial { "partial" } else { "final" }.to_string(),
+        session_id: session_id.to_string(),
+        text: text.to_string(),
+        translated_text: translated_text.map(str::to_string),
+        timestamp_ms,
+    });
+}
+
+async fn browser_source_handler() -> Html<&'static str> {
+    Html(BROWSER_SOURCE_HTML)
+}
+
+#[derive(Clone)]
+struct CaptionState {
+    port: u16,
+    token: String,
+    shutdown: watch::Receiver<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WebSocketQuery {
+    token: Option<String>,
+}
+
+/// Browsers send the page's origin with WebSocket handshakes: only the
+/// browser source served by this server may connect (other pages the user has
+/// open could read the transcript). Clients without an Origin (not a browser)
+/// still need the token.
+fn is_allowed_origin(headers: &HeaderMap, port: u16) -> bool {
+    let Some(origin) = headers.get(header::ORIGIN) else {
+        return true;
+    };
+
+    let allowed = [
+        format!("http://127.0.0.1:{}", port),
+        format!("http://localhost:{}", port),
+    ];
+    origin
+        .to_str()
+        .map(|origin| allowed.iter().any(|allowed| allowed == origin))
+        .unwrap_or(false)
+}
+
+async fn websocket_handler(
+    State(state): State<CaptionState>,
+    Query(query): Query<WebSocketQuery>,
+    headers: HeaderMap,
+    ws: WebSocketUpgrade,
+) -> Response {
+    if !is_allowed_origin(&headers, state.port) {
+        warn!("Caption client rejected (origin not allowed)");
+        return (StatusCode::FORBIDDEN, "Origin not allowed").into_response();
+    }
+    let token_valid = query
+        .token
+        .as_deref()
+        .is_some_and(|token| token_matches(token, &state.token));
+    if !token_valid {
+        warn!("Caption client rejected (missing or invalid token)");
+        return (StatusCode::UNAUTHORIZED, "Missing or invalid token").into_response();
+    }
+
+    ws.on_upgrade(move |socket| forward_captions(socket, state.shutdown))
+}
+
+/// Forward broadcast captions to one WebSocket client until it disconnects
+/// or the server is stopped
+async fn forward_captions(mut socket: WebSocket, mut shutdown: watch::Receiver<bool>) {
+    let mut captions = CAPTION_CHANNEL.subscribe();
+    debug!("Caption client connected");
+
+    loop {
+        tokio::select! {
+            caption = captions.recv() => match caption {
+                Ok(caption) => {
+                    let Ok(payload) = serde_json::to_string(&caption) else {
+                        continue;
+                    };
+                    if socket.send(Message::Text(payload)).await.is_err() {
+                        break;
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!("Caption client lagging, skipped {} messages", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Close(_))) | None | Some(Err(_)) => break,
+                Some(Ok(_)) => {} // Clients don't send anything meaningful
+            },
+            _ = shutdown.changed() => {
+                let _ = socket.send(Message::Close(None)).await;
+                break;
+            }
+        }
+    }
+
+    debug!("Caption client disconnected");
+}
+
+fn server_info(port: u16, token: &str) -> Result<CaptionServerInfo> {
+    let published_session_id = PUBLISHED_SESSION
+        .lock()
+        .map_err(|e| anyhow::anyhow!("Failed to lock published session: {}", e))?
+        .clone();
+
+    Ok(CaptionServerInfo {
+        port,
+        token: token.to_string(),
+        browser_source_url: format!("http://127.0.0.1:{}/?token={}", port, token),
+        websocket_url: format!("ws://127.0.0.1:{}/ws?token={}", port, token),
+        published_session_id,
+    })
+}
+
+/// Choose which live session is published (None = every session)
+pub fn set_published_session(session_id: Option<String>) -> Result<()> {
+    let mut published = PUBLISHED_SESSION
+        .lock()
+        .map_err(|e| anyhow::anyhow!("Failed to lock published session: {}", e))?;
+    *published = session_id;
+    Ok(())
+}
+
+/// Start the caption server on 127.0.0.1:`port` (0 = pick a free port)
+pub async fn start_caption_server(
+    port: u16,
+    session_id: Option<String>,
+) -> Result<CaptionServerInfo> {
+    if CAPTION_SERVER
+        .lock()
+        .map_err(|e| anyhow::anyhow!("Failed to lock caption server state: {}", e))?
+        .is_some()
+    {
+        anyhow::bail!("Caption server is already running");
+    }
+
+    set_published_session(session_id)?;
+
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
+
+    // Localhost only - OBS runs on the same machine
+    let listener = tokio::net::TcpListener::bind(("127.0.0.1", port))
+        .await
+        .with_context(|| format!("Failed to bind caption server to port {}", port))?;
+    let bound_port = listener
+        .local_addr()
+        .context("Failed to read caption server address")?
+        .port();
+
+    // New token per server start, so old browser source URLs stop working
+    let token = generate_token()?;
+    let router = Router::new()
+        .route("/", get(browser_source_handler))
+        .route("/ws", get(websocket_handler))
+        .with_state(CaptionState {
+            port: bound_port,
+            token: token.clone(),
+            shutdown: shutdown_rx.clone(),
+        });
+
+    let mut server_shutdown = shutdown_rx;
+    tauri::async_runtime::spawn(async move {
+        let server = axum::serve(listener, router).with_graceful_shutdown(async move {
+            let _ = server_shutdown.changed().await;
+        });
+
+        if let Err(e) = server.await {
+            warn!("Caption server stopped with error: {}", e);
+        }
+    });
+
+    CAPTION_SERVER
+        .lock()
+        .map_err(|e| anyhow::anyhow!("Failed to lock caption server state: {}", e))?
+        .replace(RunningCaptionServer {
+            port: bound_port,
+            token: token.clone(),
+            shutdown: shutdown_tx,
+        });
+
+    let info = server_info(bound_port, &token)?;
+    info!("Caption server listening on port {}", bound_port);
+    Ok(info)
+}
+
+/// Stop the caption server (no-op if it isn't running)
+pub fn stop_caption_server() -> Result<bool> {
+    let running = CAPTION_SERVER
+        .lock()
+        .map_err(|e| anyhow::anyhow!("Failed to lock caption server state: {}", e))?
+        .take();
+
+    match running {
+        Some(server) => {
+            let _ = server.shutdown.send(true);
+            info!("Caption server on port {} stopped", server.port);
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+/// Get info about the running caption server, if any
+pub fn caption_server_status() -> Result<Option<CaptionServerInfo>> {
+    let running = CAPTION_SERVER
+        .lock()
+        .map_err(|e| anyhow::anyhow!("Failed to lock caption server state: {}", e))?
+        .as_ref()
+        .map(|server| (server.port, server.token.clone()));
+
+    running
+        .map(|(port, token)| server_info(port, &token))
+        .transpose()
+}
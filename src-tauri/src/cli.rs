@@ -0,0 +1,284 @@
+//! Headless command line mode: `tauri-whisper-app transcribe <file> [options]`.
+//!
+//! Runs the same pipeline as the GUI without opening a window, so batch jobs
+//! can be scripted (e.g. `for f in *.mp4; do ... transcribe "$f" -o "$f.srt"; done`).
+
+use anyhow::{Context, Result};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::pipeline::export::{
+    generate_transcript_document, DocumentFormat, TranscriptDocumentOptions, TranscriptSegment,
+};
+use crate::pipeline::glossary::{resolve_glossary, ResolvedGlossary};
+use crate::pipeline::models::{
+    default_app_data_dir, ensure_model_fits, models_dir_in, whisper_model_path,
+};
+use crate::pipeline::subtitles::{
+    apply_subtitle_options, generate_ass, generate_srt, generate_vtt, SubtitleOptions,
+};
+use crate::pipeline::{run_transcription, TranscriptionProgress, TranscriptionResult};
+use crate::whisper_rs_imp::transcriber::{is_supported_language, TranscriptionSettings};
+
+/// Subcommands that switch the app to headless mode
+const CLI_SUBCOMMANDS: [&str; 1] = ["transcribe"];
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "tauri-whisper-app",
+    about = "Whisper subtitle generation",
+    version
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: CliCommand,
+}
+
+#[derive(Debug, Subcommand)]
+enum CliCommand {
+    /// Transcribe an audio/video file without opening the GUI
+    Transcribe(TranscribeArgs),
+}
+
+#[derive(Debug, Args)]
+struct TranscribeArgs {
+    /// Audio or video file to transcribe (any format ffmpeg can read)
+    input: PathBuf,
+
+    /// Whisper model name, must already be downloaded (e.g. "base", "small.en")
+    #[arg(short, long, default_value = "base")]
+    model: String,
+
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = OutputFormat::Srt)]
+    format: OutputFormat,
+
+    /// Output file (defaults to stdout)
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// Spoken language code (e.g. "en", "fr"), detected when "auto"
+    /// (overrides the language of the settings file)
+    #[arg(short, long, default_value = "auto", value_parser = parse_language)]
+    language: String,
+
+    /// Models directory (defaults to the app's models folder)
+    #[arg(long)]
+    models_dir: Option<PathBuf>,
+
+    /// Shift every subtitle timestamp by this many seconds (can be negative)
+    #[arg(long, default_value_t = 0.0, allow_negative_numbers = true)]
+    offset: f64,
+
+    /// Snap subtitle timestamps to frames of this frame rate (e.g. 25, 29.97)
+    #[arg(long)]
+    frame_rate: Option<f64>,
+
+    /// JSON file with advanced transcription settings (same shape as the GUI settings)
+    #[arg(long)]
+    settings: Option<PathBuf>,
+
+    /// Load the model even if it likely doesn't fit in the available memory
+    #[arg(long)]
+    ignore_memory_check: bool,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum OutputFormat {
+    Srt,
+    Vtt,
+    Ass,
+    Txt,
+    Json,
+    /// Transcript document with paragraphs and chapters (meeting notes)
+    Md,
+    Html,
+}
+
+/// `--language` value: "auto" or a language code Whisper knows
+fn parse_language(value: &str) -> Result<String, String> {
+    let language = value.trim().to_lowercase();
+
+    if language == "auto" || is_supported_language(&language) {
+        Ok(language)
+    } else {
+        Err(format!(
+            "unsupported language code '{}' (e.g. \"en\", \"fr\" or \"auto\")",
+            value
+        ))
+    }
+}
+
+/// True when the process was started with a CLI subcommand instead of for the GUI
+pub fn is_cli_invocation() -> bool {
+    std::env::args()
+        .nth(1)
+        .map(|arg| CLI_SUBCOMMANDS.contains(&arg.as_str()))
+        .unwrap_or(false)
+}
+
+/// Release builds use the "windows" subsystem (no console), so re-attach to the
+/// terminal that launched us, otherwise nothing printed would be visible
+#[cfg(target_os = "windows")]
+fn attach_parent_console() {
+    use windows_sys::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn attach_parent_console() {}
+
+fn print_progress(progress: TranscriptionProgress) {
+    match progress {
+        TranscriptionProgress::Converting { message } => eprintln!("{}", message),
+        TranscriptionProgress::Preprocessed { report } => {
+            let format_level = |level: Option<f32>| {
+                level
+                    .map(|db| format!("{:.1} dB", db))
+                    .unwrap_or_else(|| "?".to_string())
+            };
+            eprintln!(
+                "Applied {} (RMS {} -> {})",
+                report.filters,
+                format_level(report.rms_before_db),
+                format_level(report.rms_after_db)
+            )
+        }
+        TranscriptionProgress::DetectingLanguage => eprintln!("Detecting language..."),
+        TranscriptionProgress::LanguageDetected { language } => {
+            eprintln!("Language: {}", language)
+        }
+        TranscriptionProgress::Transcribing { progress } => {
+            eprintln!("Transcribing... ({}%)", progress)
+        }
+        TranscriptionProgress::GeneratingSubtitles => eprintln!("Generating subtitles..."),
+        TranscriptionProgress::Complete { .. } => eprintln!("Done"),
+    }
+}
+
+fn load_settings(path: &Path) -> Result<TranscriptionSettings> {
+    let json = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read settings file: {}", path.display()))?;
+
+    serde_json::from_str(&json)
+        .with_context(|| format!("Invalid settings file: {}", path.display()))
+}
+
+fn format_output(result: &TranscriptionResult, format: OutputFormat) -> Result<String> {
+    let output = match format {
+        OutputFormat::Srt => result.subtitles_srt.clone(),
+        OutputFormat::Vtt => result.subtitles_vtt.clone(),
+        OutputFormat::Ass => generate_ass(&result.segments),
+        OutputFormat::Txt => format!("{}\n", result.text.trim()),
+        OutputFormat::Json => {
+            serde_json::to_string_pretty(result).context("Failed to serialize result")?
+        }
+        OutputFormat::Md | OutputFormat::Html => {
+            let segments: Vec<TranscriptSegment> = result
+                .segments
+                .iter()
+                .map(TranscriptSegment::from)
+                .collect();
+            let options = TranscriptDocumentOptions {
+                format: if matches!(format, OutputFormat::Html) {
+                    DocumentFormat::Html
+                } else {
+                    DocumentFormat::Markdown
+                },
+                ..Default::default()
+            };
+            generate_transcript_document(&segments, &options)?.content
+        }
+    };
+
+    Ok(output)
+}
+
+fn transcribe(args: TranscribeArgs) -> Result<()> {
+    let models_dir = match args.models_dir {
+        Some(dir) => dir,
+        None => models_dir_in(&default_app_data_dir()?)?,
+    };
+    let model_path = whisper_model_path(&models_dir, &args.model)?;
+    ensure_model_fits(&args.model, &model_path, args.ignore_memory_check)?;
+
+    let mut settings = args.settings.as_deref().map(load_settings).transpose()?;
+    if args.language != "auto" {
+        settings
+            .get_or_insert_with(TranscriptionSettings::default)
+            .language = Some(args.language.clone());
+    }
+
+    // Glossaries are shared with the GUI (stored in the app data directory)
+    let glossary = match settings.as_ref().and_then(|s| s.glossary.as_ref()) {
+        Some(_) => resolve_glossary(&default_app_data_dir()?, settings.as_ref())?,
+        None => ResolvedGlossary::default(),
+    };
+
+    // Unique temp folder so several CLI runs can work in parallel
+    let temp_dir = std::env::temp_dir().join(format!("tauri-whisper-cli-{}", std::process::id()));
+
+    let result = run_transcription(
+        &args.input,
+        &model_path,
+        &temp_dir,
+        true, // ignored when a language is set
+        settings,
+        &glossary,
+        &print_progress,
+    );
+    let _ = fs::remove_dir_all(&temp_dir);
+    let mut result = result?;
+
+    // Re-time the subtitles for the video they'll be muxed into
+    if args.offset != 0.0 || args.frame_rate.is_some() {
+        let options = SubtitleOptions {
+            offset_seconds: args.offset,
+            frame_rate: args.frame_rate,
+        };
+        result.segments = apply_subtitle_options(&result.segments, &options)?;
+        result.subtitles_srt = generate_srt(&result.segments);
+        result.subtitles_vtt = generate_vtt(&result.segments);
+    }
+
+    let output = format_output(&result, args.format)?;
+
+    match args.output {
+        Some(path) => fs::write(&path, output)
+            .with_context(|| format!("Failed to write output file: {}", path.display()))?,
+        None => print!("{}", output),
+    }
+
+    Ok(())
+}
+
+/// Parse the command line and run it. Returns the process exit code
+/// (0 = success, 1 = transcription failed, 2 = invalid arguments).
+pub fn run() -> i32 {
+    attach_parent_console();
+
+    let cli = match Cli::try_parse() {
+        Ok(cli) => cli,
+        Err(e) => {
+            // Also handles --help / --version (exit code 0)
+            let _ = e.print();
+            return e.exit_code();
+        }
+    };
+
+    let outcome = match cli.command {
+        CliCommand::Transcribe(args) => transcribe(args),
+    };
+
+    match outcome {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            1
+        }
+    }
+}
@@ -0,0 +1,808 @@
+//! Push-to-talk dictation: a global shortcut records the microphone (captured
+//! natively, the app window doesn't need focus) and the transcript is copied to
+//! the clipboard or typed into the focused application.
+//!
+//! The state is shown by a tray icon and sent to the frontend as
+//! "dictation-status" events, transcripts as "dictation-result" events.
+
+use anyhow::{Context, Result};
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{FromSample, Sample, SizedSample};
+use enigo::{Enigo, Keyboard, Settings as EnigoSettings};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use tauri::image::Image;
+use tauri::tray::TrayIconBuilder;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+use tracing::{debug, info, warn};
+
+use crate::get_models_dir_internal;
+use crate::pipeline::models::whisper_model_path;
+use crate::whisper_rs_imp::live_transcriber::{transcribe_pcm_recording, LiveTranscriptionContext};
+
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+use crate::vosk_live_transcriber::{VoskLiveSession, VoskSessionOptions};
+
+pub const DICTATION_STATUS_EVENT: &str = "dictation-status";
+pub const DICTATION_PARTIAL_EVENT: &str = "dictation-partial";
+pub const DICTATION_RESULT_EVENT: &str = "dictation-result";
+
+/// Recordings are stopped automatically after this long (seconds)
+const MAX_DICTATION_SECONDS: u64 = 300;
+
+/// Shorter recordings are accidental taps, nothing is transcribed (seconds)
+const MIN_DICTATION_SECONDS: f32 = 0.5;
+
+/// Toggle mode ignores presses this soon after the start (auto-repeating keys)
+const TOGGLE_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// The shortcut's modifiers may still be held when the text is ready,
+/// typing right away would trigger the focused app's shortcuts
+const TYPING_DELAY: Duration = Duration::from_millis(200);
+
+/// The tray icon only exists while dictation is enabled
+const TRAY_ID: &str = "dictation";
+const RECORDING_TINT: [u8; 3] = [230, 40, 40];
+const TRANSCRIBING_TINT: [u8; 3] = [240, 170, 30];
+
+/// Start/stop cues: two short tones, rising then falling (Hz)
+const START_CUE: [f32; 2] = [660.0, 880.0];
+const STOP_CUE: [f32; 2] = [880.0, 660.0];
+const CUE_TONE_SECONDS: f32 = 0.08;
+const CUE_VOLUME: f32 = 0.2;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DictationMode {
+    /// Record while the shortcut is held
+    #[default]
+    PushToTalk,
+    /// First press starts recording, second press stops it
+    Toggle,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DictationEngine {
+    /// Transcribed once the recording stops (more accurate)
+    #[default]
+    Whisper,
+    /// Transcribed while recording, partial results are emitted (Windows/Linux)
+    Vosk,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DictationOutput {
+    #[default]
+    Clipboard,
+    /// Type the text into the focused application (simulated keyboard)
+    Type,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DictationSettings {
+    /// Global shortcut (e.g. "CommandOrControl+Shift+Space", "Alt+F9")
+    pub shortcut: String,
+    pub mode: DictationMode,
+    pub engine: DictationEngine,
+    /// Whisper model name (e.g. "base", "small.en") or Vosk model folder
+    pub model_name: String,
+    pub output: DictationOutput,
+    /// Play a short cue when recording starts and stops
+    pub play_sounds: bool,
+    /// Load the Whisper model even if it likely doesn't fit in memory
+    pub ignore_memory_check: bool,
+}
+
+impl Default for DictationSettings {
+    fn default() -> Self {
+        Self {
+            shortcut: "CommandOrControl+Shift+Space".to_string(),
+            mode: DictationMode::PushToTalk,
+            engine: DictationEngine::Whisper,
+            model_name: "base".to_string(),
+            output: DictationOutput::Clipboard,
+            play_sounds: true,
+            ignore_memory_check: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DictationPhase {
+    Disabled,
+    Idle,
+    Recording,
+    Transcribing,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DictationStatus {
+    pub phase: DictationPhase,
+    pub settings: Option<DictationSettings>, // None when disabled
+}
+
+/// Payload of "dictation-result" events
+#[derive(Debug, Clone, Serialize)]
+pub struct DictationResult {
+    pub text: String, // empty when nothing was said
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+struct DictationController {
+    settings: Option<DictationSettings>,
+    shortcut: Option<Shortcut>,
+    phase: DictationPhase,
+    stop_requested: Arc<AtomicBool>, // of the current recording
+    recording_started: Option<Instant>,
+}
+
+impl DictationController {
+    fn status(&self) -> DictationStatus {
+        DictationStatus {
+            phase: self.phase,
+            settings: self.settings.clone(),
+        }
+    }
+}
+
+static DICTATION: Lazy<Mutex<DictationController>> = Lazy::new(|| {
+    Mutex::new(DictationController {
+        settings: None,
+        shortcut: None,
+        phase: DictationPhase::Disabled,
+        stop_requested: Arc::new(AtomicBool::new(false)),
+        recording_started: None,
+    })
+});
+
+// Dictation's own Whisper model, kept loaded between recordings without
+// evicting the live transcription model (they usually differ)
+static DICTATION_CONTEXT: Lazy<Arc<Mutex<LiveTranscriptionContext>>> =
+    Lazy::new(|| Arc::new(Mutex::new(LiveTranscriptionContext::new())));
+
+fn lock_controller() -> Result<MutexGuard<'static, DictationController>> {
+    DICTATION
+        .lock()
+        .map_err(|e| anyhow::anyhow!("Failed to lock dictation state: {}", e))
+}
+
+pub fn dictation_status() -> Result<DictationStatus> {
+    Ok(lock_controller()?.status())
+}
+
+fn parse_shortcut(shortcut: &str) -> Result<Shortcut> {
+    shortcut
+        .parse::<Shortcut>()
+        .map_err(|e| anyhow::anyhow!("Invalid shortcut '{}': {}", shortcut, e))
+}
+
+fn resolve_model_path(app: &AppHandle, settings: &DictationSettings) -> Result<PathBuf> {
+    let models_dir = get_models_dir_internal(app)?;
+
+    match settings.engine {
+        DictationEngine::Whisper => whisper_model_path(&models_dir, &settings.model_name),
+        DictationEngine::Vosk => {
+            if cfg!(not(any(target_os = "windows", target_os = "linux"))) {
+                anyhow::bail!(
+                    "Vosk is only available on Windows and Linux, use a Whisper model for dictation"
+                );
+            }
+
+            let model_path = models_dir.join(&settings.model_name);
+            if !model_path.exists() {
+                anyhow::bail!(
+                    "Vosk model '{}' not found. Please download it first.",
+                    settings.model_name
+                );
+            }
+            Ok(model_path)
+        }
+    }
+}
+
+/// Enable dictation with `settings` (registering its global shortcut),
+/// or disable it with None
+pub fn configure(app: &AppHandle, settings: Option<DictationSettings>) -> Result<DictationStatus> {
+    // Validate everything before touching the registered shortcut
+    let new_shortcut = settings
+        .as_ref()
+        .map(|settings| parse_shortcut(&settings.shortcut))
+        .transpose()?;
+    if let Some(settings) = &settings {
+        resolve_model_path(app, settings)?;
+    }
+
+    // Shortcuts are (un)registered without holding the lock, the shortcut
+    // handler runs on the main thread and locks it too
+    let old_shortcut = {
+        let controller = lock_controller()?;
+        if matches!(
+            controller.phase,
+            DictationPhase::Recording | DictationPhase::Transcribing
+        ) {
+            anyhow::bail!("Dictation settings can't be changed while dictating");
+        }
+        controller.shortcut
+    };
+
+    let global_shortcut = app.global_shortcut();
+    if let Some(old_shortcut) = old_shortcut {
+        global_shortcut
+            .unregister(old_shortcut)
+            .context("Failed to unregister the dictation shortcut")?;
+    }
+
+    let registered = match new_shortcut {
+        Some(shortcut) => global_shortcut
+            .register(shortcut)
+            .map(|_| Some(shortcut))
+            .with_context(|| {
+                format!(
+                    "Failed to register shortcut '{}' (already used by another application?)",
+                    settings.as_ref().map(|s| s.shortcut.as_str()).unwrap_or("")
+                )
+            }),
+        None => Ok(None),
+    };
+
+    // A failed registration leaves dictation disabled (the old shortcut is gone)
+    let status = {
+        let mut controller = lock_controller()?;
+        match &registered {
+            Ok(Some(shortcut)) => {
+                controller.shortcut = Some(*shortcut);
+                controller.settings = settings;
+                controller.phase = DictationPhase::Idle;
+            }
+            _ => {
+                controller.shortcut = None;
+                controller.settings = None;
+                controller.phase = DictationPhase::Disabled;
+            }
+        }
+        controller.status()
+    };
+    publish_status(app, &status);
+
+    // Free the dictation model while disabled, it's loaded again on the next recording
+    if status.settings.is_none() {
+        if let Ok(mut context) = DICTATION_CONTEXT.lock() {
+            *context = LiveTranscriptionContext::new();
+        }
+    }
+    registered?;
+
+    info!("Dictation configured ({:?})", status.settings);
+    Ok(status)
+}
+
+/// Global shortcut handler (registered with the plugin in `main`)
+pub fn handle_shortcut(app: &AppHandle, shortcut: &Shortcut, state: ShortcutState) {
+    let (settings, should_start) = {
+        let Ok(controller) = lock_controller() else {
+            return;
+        };
+        if controller.shortcut.as_ref() != Some(shortcut) {
+            return;
+        }
+        let Some(settings) = controller.settings.clone() else {
+            return;
+        };
+
+        let can_toggle = controller
+            .recording_started
+            .is_some_and(|started| started.elapsed() >= TOGGLE_DEBOUNCE);
+        let should_start = match (state, controller.phase, settings.mode) {
+            (ShortcutState::Pressed, DictationPhase::Idle, _) => true,
+            (ShortcutState::Released, DictationPhase::Recording, DictationMode::PushToTalk) => {
+                false
+            }
+            (ShortcutState::Pressed, DictationPhase::Recording, DictationMode::Toggle)
+                if can_toggle =>
+            {
+                false
+            }
+            (ShortcutState::Pressed, DictationPhase::Transcribing, _) => {
+                debug!("Previous dictation still transcribing, shortcut ignored");
+                return;
+            }
+            _ => return,
+        };
+        (settings, should_start)
+    };
+
+    if !should_start {
+        stop_recording(app);
+        return;
+    }
+
+    if let Err(e) = start_recording(app, settings) {
+        warn!("Failed to start dictation: {:#}", e);
+        let result = DictationResult {
+            text: String::new(),
+            error: Some(format!("{:#}", e)),
+        };
+        app.emit(DICTATION_RESULT_EVENT, result).ok();
+    }
+}
+
+fn start_recording(app: &AppHandle, settings: DictationSettings) -> Result<()> {
+    let model_path = resolve_model_path(app, &settings)?;
+    let stop_requested = Arc::new(AtomicBool::new(false));
+
+    let status = {
+        let mut controller = lock_controller()?;
+        controller.phase = DictationPhase::Recording;
+        controller.stop_requested = stop_requested.clone();
+        controller.recording_started = Some(Instant::now());
+        controller.status()
+    };
+    publish_status(app, &status);
+
+    if settings.play_sounds {
+        play_cue(&START_CUE);
+    }
+    info!(
+        "Dictation started ({:?}, {})",
+        settings.engine, settings.model_name
+    );
+
+    let app = app.clone();
+    std::thread::spawn(move || run_dictation(app, settings, model_path, stop_requested));
+
+    Ok(())
+}
+
+/// Stop the current recording (shortcut released/pressed again, time limit
+/// or capture error), does nothing if not recording
+fn stop_recording(app: &AppHandle) {
+    let (status, play_sounds) = {
+        let Ok(mut controller) = lock_controller() else {
+            return;
+        };
+        if controller.phase != DictationPhase::Recording {
+            return;
+        }
+
+        controller.stop_requested.store(true, Ordering::SeqCst);
+        controller.phase = DictationPhase::Transcribing;
+        controller.recording_started = None;
+
+        let play_sounds = controller.settings.as_ref().is_some_and(|s| s.play_sounds);
+        (controller.status(), play_sounds)
+    };
+    publish_status(app, &status);
+
+    if play_sounds {
+        play_cue(&STOP_CUE);
+    }
+}
+
+/// Recording thread: capture, transcribe, output the text and go back to idle
+fn run_dictation(
+    app: AppHandle,
+    settings: DictationSettings,
+    model_path: PathBuf,
+    stop_requested: Arc<AtomicBool>,
+) {
+    let outcome =
+        record_and_transcribe(&app, &settings, &model_path, &stop_requested).and_then(|text| {
+            if !text.is_empty() {
+                deliver_text(&app, settings.output, &text)?;
+            }
+            Ok(text)
+        });
+
+    // No-op unless capture failed while recording
+    stop_recording(&app);
+
+    let result = match outcome {
+        Ok(text) => {
+            info!("Dictation finished ({} characters)", text.chars().count());
+            DictationResult { text, error: None }
+        }
+        Err(e) => {
+            warn!("Dictation failed: {:#}", e);
+            DictationResult {
+                text: String::new(),
+                error: Some(format!("{:#}", e)),
+            }
+        }
+    };
+    app.emit(DICTATION_RESULT_EVENT, result).ok();
+
+    let status = {
+        let Ok(mut controller) = lock_controller() else {
+            return;
+        };
+        if controller.phase == DictationPhase::Transcribing {
+            controller.phase = DictationPhase::Idle;
+        }
+        controller.status()
+    };
+    publish_status(&app, &status);
+}
+
+fn record_and_transcribe(
+    app: &AppHandle,
+    settings: &DictationSettings,
+    model_path: &PathBuf,
+    stop_requested: &AtomicBool,
+) -> Result<String> {
+    let capture = MicrophoneCapture::start()?;
+    let sample_rate = capture.sample_rate;
+
+    match settings.engine {
+        DictationEngine::Whisper => {
+            let mut samples = Vec::new();
+            capture.record(stop_requested, |chunk| samples.extend_from_slice(chunk));
+            stop_recording(app);
+
+            if (samples.len() as f32) < sample_rate * MIN_DICTATION_SECONDS {
+                debug!("Dictation too short, ignored");
+                return Ok(String::new());
+            }
+
+            let result = transcribe_pcm_recording(
+                &samples,
+                sample_rate,
+                &DICTATION_CONTEXT,
+                model_path,
+                settings.ignore_memory_check,
+            )?;
+            Ok(result.text)
+        }
+        DictationEngine::Vosk => record_with_vosk(app, capture, model_path, stop_requested),
+    }
+}
+
+/// Transcribe while recording, partial results are sent as "dictation-partial" events
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+fn record_with_vosk(
+    app: &AppHandle,
+    capture: MicrophoneCapture,
+    model_path: &PathBuf,
+    stop_requested: &AtomicBool,
+) -> Result<String> {
+    // The capture keeps queuing audio while the model loads
+    let mut session = VoskLiveSession::new(
+        model_path,
+        capture.sample_rate,
+        VoskSessionOptions::default(),
+        None,
+    )?;
+
+    let mut utterances: Vec<String> = Vec::new();
+    let mut last_preview = String::new();
+
+    capture.record(stop_requested, |chunk| {
+        let result = session.process_chunk(chunk);
+        if result.text.is_empty() {
+            return;
+        }
+
+        if result.is_partial {
+            let preview = utterances
+                .iter()
+                .map(String::as_str)
+                .chain(std::iter::once(result.text.as_str()))
+                .collect::<Vec<_>>()
+                .join(" ");
+            if preview != last_preview {
+                app.emit(DICTATION_PARTIAL_EVENT, &preview).ok();
+                last_preview = preview;
+            }
+        } else {
+            utterances.push(result.text);
+        }
+    });
+    stop_recording(app);
+
+    let last_utterance = session.finalize();
+    if !last_utterance.is_empty() {
+        utterances.push(last_utterance);
+    }
+
+    Ok(utterances.join(" "))
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+fn record_with_vosk(
+    _app: &AppHandle,
+    _capture: MicrophoneCapture,
+    _model_path: &PathBuf,
+    _stop_requested: &AtomicBool,
+) -> Result<String> {
+    anyhow::bail!("Vosk is only available on Windows and Linux, use a Whisper model for dictation")
+}
+
+fn deliver_text(app: &AppHandle, output: DictationOutput, text: &str) -> Result<()> {
+    match output {
+        DictationOutput::Clipboard => app
+            .clipboard()
+            .write_text(text)
+            .context("Failed to copy the text to the clipboard"),
+        DictationOutput::Type => {
+            std::thread::sleep(TYPING_DELAY);
+
+            let mut enigo = Enigo::new(&EnigoSettings::default())
+                .context("Failed to access the keyboard to type the text")?;
+            enigo.text(text).context("Failed to type the text")
+        }
+    }
+}
+
+// ============================================================================
+// MICROPHONE CAPTURE
+// ============================================================================
+
+/// Native microphone capture, sends mono 16-bit PCM chunks to `chunks`.
+/// cpal streams can't be moved across threads, so the capture lives on the
+/// recording thread and stops when dropped.
+struct MicrophoneCapture {
+    stream: cpal::Stream,
+    chunks: mpsc::Receiver<Vec<i16>>,
+    sample_rate: f32,
+}
+
+impl MicrophoneCapture {
+    fn start() -> Result<Self> {
+        let device = cpal::default_host()
+            .default_input_device()
+            .context("No microphone found")?;
+        let supported_config = device
+            .default_input_config()
+            .context("Failed to get the microphone configuration")?;
+        let sample_format = supported_config.sample_format();
+        let config: cpal::StreamConfig = supported_config.into();
+
+        let (sender, chunks) = mpsc::channel();
+        let stream = match sample_format {
+            cpal::SampleFormat::I16 => build_input_stream::<i16>(&device, &config, sender),
+            cpal::SampleFormat::U16 => build_input_stream::<u16>(&device, &config, sender),
+            cpal::SampleFormat::F32 => build_input_stream::<f32>(&device, &config, sender),
+            other => anyhow::bail!("Unsupported microphone sample format: {:?}", other),
+        }?;
+        stream.play().context("Failed to start the microphone")?;
+
+        debug!(
+            "Microphone capture started ({} Hz, {} channel(s))",
+            config.sample_rate.0, config.channels
+        );
+
+        Ok(Self {
+            stream,
+            chunks,
+            sample_rate: config.sample_rate.0 as f32,
+        })
+    }
+
+    /// Pass audio to `on_chunk` until `stop_requested` is set (or the time
+    /// limit is reached), then stop the microphone
+    fn record(self, stop_requested: &AtomicBool, mut on_chunk: impl FnMut(&[i16])) {
+        let deadline = Instant::now() + Duration::from_secs(MAX_DICTATION_SECONDS);
+
+        while !stop_requested.load(Ordering::SeqCst) {
+            if Instant::now() >= deadline {
+                warn!(
+                    "Dictation stopped after {}s (time limit)",
+                    MAX_DICTATION_SECONDS
+                );
+                break;
+            }
+
+            match self.chunks.recv_timeout(Duration::from_millis(50)) {
+                Ok(chunk) => on_chunk(&chunk),
+                Err(mpsc::RecvTimeoutError::Timeout) => {}
+                Err(mpsc::RecvTimeoutError::Disconnected) => break,
+            }
+        }
+
+        // Keep the audio captured before the stop request
+        drop(self.stream);
+        for chunk in self.chunks.try_iter() {
+            on_chunk(&chunk);
+        }
+    }
+}
+
+fn build_input_stream<T>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    sender: mpsc::Sender<Vec<i16>>,
+) -> Result<cpal::Stream>
+where
+    T: SizedSample,
+    i16: FromSample<T>,
+{
+    let channels = config.channels.max(1) as usize;
+
+    device
+        .build_input_stream(
+            config,
+            move |data: &[T], _: &cpal::InputCallbackInfo| {
+                // Downmix to mono
+                let mono: Vec<i16> = data
+                    .chunks(channels)
+                    .map(|frame| {
+                        let sum: i32 = frame
+                            .iter()
+                            .map(|sample| sample.to_sample::<i16>() as i32)
+                            .sum();
+                        (sum / frame.len() as i32) as i16
+                    })
+                    .collect();
+                let _ = sender.send(mono);
+            },
+            |e| warn!("Microphone stream error: {}", e),
+            None,
+        )
+        .context("Failed to open the microphone")
+}
+
+// ============================================================================
+// CUES & TRAY
+// ============================================================================
+
+/// Play a cue on the default output device in the background
+/// (best effort, failures are only logged)
+fn play_cue(frequencies: &'static [f32]) {
+    std::thread::spawn(move || {
+        if let Err(e) = play_tones(frequencies) {
+            debug!("Failed to play dictation cue: {:#}", e);
+        }
+    });
+}
+
+fn play_tones(frequencies: &[f32]) -> Result<()> {
+    let device = cpal::default_host()
+        .default_output_device()
+        .context("No audio output device")?;
+    let supported_config = device
+        .default_output_config()
+        .context("Failed to get the audio output configuration")?;
+    let sample_format = supported_config.sample_format();
+    let config: cpal::StreamConfig = supported_config.into();
+
+    let sample_rate = config.sample_rate.0 as f32;
+    let tones = tone_samples(frequencies, sample_rate);
+    let duration = Duration::from_secs_f32(tones.len() as f32 / sample_rate);
+
+    let stream = match sample_format {
+        cpal::SampleFormat::I16 => build_output_stream::<i16>(&device, &config, tones),
+        cpal::SampleFormat::U16 => build_output_stream::<u16>(&device, &config, tones),
+        cpal::SampleFormat::F32 => build_output_stream::<f32>(&device, &config, tones),
+        other => anyhow::bail!("Unsupported output sample format: {:?}", other),
+    }?;
+    stream.play().context("Failed to start the audio output")?;
+
+    // The stream stops when dropped
+    std::thread::sleep(duration + Duration::from_millis(50));
+    Ok(())
+}
+
+/// Mono samples of the cue, each tone fades in and out to avoid clicks
+fn tone_samples(frequencies: &[f32], sample_rate: f32) -> Vec<f32> {
+    let tone_length = (CUE_TONE_SECONDS * sample_rate) as usize;
+    let fade_length = (tone_length / 8).max(1);
+
+    frequencies
+        .iter()
+        .flat_map(|&frequency| {
+            (0..tone_length).map(move |i| {
+                let edge_distance = i.min(tone_length - 1 - i);
+                let envelope = (edge_distance as f32 / fade_length as f32).min(1.0);
+                let phase = 2.0 * std::f32::consts::PI * frequency * i as f32 / sample_rate;
+                CUE_VOLUME * envelope * phase.sin()
+            })
+        })
+        .collect()
+}
+
+fn build_output_stream<T>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    tones: Vec<f32>,
+) -> Result<cpal::Stream>
+where
+    T: SizedSample + FromSample<f32>,
+{
+    let channels = config.channels.max(1) as usize;
+    let mut position = 0;
+
+    device
+        .build_output_stream(
+            config,
+            move |data: &mut [T], _: &cpal::OutputCallbackInfo| {
+                for frame in data.chunks_mut(channels) {
+                    let value = tones.get(position).copied().unwrap_or(0.0);
+                    frame.fill(T::from_sample(value));
+                    position += 1;
+                }
+            },
+            |e| debug!("Cue stream error: {}", e),
+            None,
+        )
+        .context("Failed to open the audio output")
+}
+
+/// Blend the app icon towards `tint`, keeping its shape (alpha)
+fn tinted_icon(icon: &Image<'_>, tint: [u8; 3]) -> Image<'static> {
+    let blend = |value: u8, target: u8| ((value as u16 + 2 * target as u16) / 3) as u8;
+
+    let rgba = icon
+        .rgba()
+        .chunks_exact(4)
+        .flat_map(|pixel| {
+            [
+                blend(pixel[0], tint[0]),
+                blend(pixel[1], tint[1]),
+                blend(pixel[2], tint[2]),
+                pixel[3],
+            ]
+        })
+        .collect();
+
+    Image::new_owned(rgba, icon.width(), icon.height())
+}
+
+/// Show the dictation state in the tray (removed when disabled)
+fn update_tray(app: &AppHandle, status: &DictationStatus) -> Result<()> {
+    let Some(settings) = status
+        .settings
+        .as_ref()
+        .filter(|_| status.phase != DictationPhase::Disabled)
+    else {
+        app.remove_tray_by_id(TRAY_ID);
+        return Ok(());
+    };
+
+    let Some(base_icon) = app.default_window_icon().cloned() else {
+        return Ok(());
+    };
+    let (icon, tooltip) = match status.phase {
+        DictationPhase::Recording => (
+            tinted_icon(&base_icon, RECORDING_TINT),
+            "Whisperer - Recording...".to_string(),
+        ),
+        DictationPhase::Transcribing => (
+            tinted_icon(&base_icon, TRANSCRIBING_TINT),
+            "Whisperer - Transcribing...".to_string(),
+        ),
+        _ => (
+            base_icon.to_owned(),
+            format!("Whisperer - Dictation ready ({})", settings.shortcut),
+        ),
+    };
+
+    let tray = match app.tray_by_id(TRAY_ID) {
+        Some(tray) => tray,
+        None => TrayIconBuilder::with_id(TRAY_ID)
+            .build(app)
+            .context("Failed to create the tray icon")?,
+    };
+    tray.set_icon(Some(icon))
+        .context("Failed to update the tray icon")?;
+    tray.set_tooltip(Some(tooltip))
+        .context("Failed to update the tray tooltip")?;
+
+    Ok(())
+}
+
+fn publish_status(app: &AppHandle, status: &DictationStatus) {
+    if let Err(e) = update_tray(app, status) {
+        warn!("{:#}", e);
+    }
+    app.emit(DICTATION_STATUS_EVENT, status).ok();
+}
@@ -0,0 +1,312 @@
+use anyhow::Result;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::Notify;
+use tracing::{debug, info, warn};
+
+use crate::pipeline::TranscriptionProgress;
+
+/// Jobs allowed to transcribe at the same time (each one loads its own model)
+pub const DEFAULT_MAX_CONCURRENT_JOBS: usize = 2;
+
+/// Finished jobs kept around for `list_jobs` before the oldest are forgotten
+const MAX_FINISHED_JOBS: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    Queued, // waiting for a free slot
+    Running,
+    Completed,
+    Failed,
+}
+
+/// Snapshot of a job, returned to the frontend
+#[derive(Debug, Clone, Serialize)]
+pub struct JobInfo {
+    pub job_id: String,
+    pub kind: String, // "transcription" or "range"
+    pub file_path: String,
+    pub model_name: String,
+    pub status: JobStatus,
+    pub last_progress: Option<TranscriptionProgress>,
+    pub error: Option<String>,
+    pub created_at_ms: u64,
+    pub started_at_ms: Option<u64>,
+    pub finished_at_ms: Option<u64>,
+}
+
+struct JobManagerState {
+    jobs: Vec<JobInfo>, // oldest first
+    running: usize,
+    max_concurrent: usize,
+}
+
+/// Tracks transcription jobs and limits how many run at once
+pub struct JobManager {
+    state: Mutex<JobManagerState>,
+    slot_freed: Notify,
+    next_id: AtomicU64,
+}
+
+pub static JOB_MANAGER: Lazy<JobManager> = Lazy::new(JobManager::new);
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Progress event name for a job (`transcription-progress:{job_id}`)
+pub fn progress_event_name(job_id: &str) -> String {
+    format!("transcription-progress:{}", job_id)
+}
+
+/// Job ids end up in event names and folder names, keep them simple
+fn validate_job_id(job_id: &str) -> Result<()> {
+    let is_valid = !job_id.is_empty()
+        && job_id.len() <= 64
+        && job_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+
+    if !is_valid {
+        anyhow::bail!(
+            "Invalid job id '{}' (use letters, digits, '-' and '_' only)",
+            job_id
+        );
+    }
+
+    Ok(())
+}
+
+impl JobManager {
+    fn new() -> Self {
+        Self {
+            state: Mutex::new(JobManagerState {
+                jobs: Vec::new(),
+                running: 0,
+                max_concurrent: DEFAULT_MAX_CONCURRENT_JOBS,
+            }),
+            slot_freed: Notify::new(),
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, JobManagerState>> {
+        self.state
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Failed to lock job manager: {}", e))
+    }
+
+    fn update_job(&self, job_id: &str, update: impl FnOnce(&mut JobInfo)) {
+        if let Ok(mut state) = self.lock() {
+            if let Some(job) = state.jobs.iter_mut().find(|job| job.job_id == job_id) {
+                update(job);
+            }
+        }
+    }
+
+    /// Register a new queued job.
+    ///
+    /// `job_id` lets the frontend pick the id up front, so it can subscribe to
+    /// the progress event before invoking the command. Generated when omitted.
+    pub fn create_job(
+        &self,
+        kind: &str,
+        file_path: &str,
+        model_name: &str,
+        job_id: Option<String>,
+    ) -> Result<JobHandle> {
+        let job_id = match job_id {
+            Some(id) => {
+                validate_job_id(&id)?;
+                id
+            }
+            // Timestamped so ids stay unique across restarts (results are saved by job id)
+            None => format!(
+                "job-{}-{}",
+                now_ms(),
+                self.next_id.fetch_add(1, Ordering::Relaxed)
+            ),
+        };
+
+        let mut state = self.lock()?;
+        if state.jobs.iter().any(|job| job.job_id == job_id) {
+            anyhow::bail!("Job '{}' already exists", job_id);
+        }
+
+        // Forget the oldest finished jobs (queued/running ones are always kept)
+        let finished = state
+            .jobs
+            .iter()
+            .filter(|job| matches!(job.status, JobStatus::Completed | JobStatus::Failed))
+            .count();
+        let mut to_forget = (finished + 1).saturating_sub(MAX_FINISHED_JOBS);
+        state.jobs.retain(|job| {
+            let is_finished = matches!(job.status, JobStatus::Completed | JobStatus::Failed);
+            if is_finished && to_forget > 0 {
+                to_forget -= 1;
+                return false;
+            }
+            true
+        });
+
+        state.jobs.push(JobInfo {
+            job_id: job_id.clone(),
+            kind: kind.to_string(),
+            file_path: file_path.to_string(),
+            model_name: model_name.to_string(),
+            status: JobStatus::Queued,
+            last_progress: None,
+            error: None,
+            created_at_ms: now_ms(),
+            started_at_ms: None,
+            finished_at_ms: None,
+        });
+
+        debug!("Job {} created ({})", job_id, kind);
+
+        Ok(JobHandle {
+            job_id,
+            holds_slot: false,
+            finished: false,
+        })
+    }
+
+    /// Record the latest progress event of a job
+    pub fn report_progress(&self, job_id: &str, progress: &TranscriptionProgress) {
+        self.update_job(job_id, |job| job.last_progress = Some(progress.clone()));
+    }
+
+    pub fn list_jobs(&self) -> Result<Vec<JobInfo>> {
+        Ok(self.lock()?.jobs.clone())
+    }
+
+    pub fn job_status(&self, job_id: &str) -> Result<JobInfo> {
+        self.lock()?
+            .jobs
+            .iter()
+            .find(|job| job.job_id == job_id)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Job '{}' not found", job_id))
+    }
+
+    pub fn max_concurrent_jobs(&self) -> Result<usize> {
+        Ok(self.lock()?.max_concurrent)
+    }
+
+    /// Change the concurrency limit (running jobs are never interrupted)
+    pub fn set_max_concurrent_jobs(&self, max_concurrent: usize) -> Result<()> {
+        if max_concurrent == 0 {
+            anyhow::bail!("At least one concurrent job is required");
+        }
+
+        self.lock()?.max_concurrent = max_concurrent;
+        info!("Max concurrent jobs set to {}", max_concurrent);
+
+        // Queued jobs may be able to start now
+        self.slot_freed.notify_waiters();
+        Ok(())
+    }
+
+    fn try_take_slot(&self, job_id: &str) -> Result<bool> {
+        let mut state = self.lock()?;
+        if state.running >= state.max_concurrent {
+            return Ok(false);
+        }
+
+        state.running += 1;
+        if let Some(job) = state.jobs.iter_mut().find(|job| job.job_id == job_id) {
+            job.status = JobStatus::Running;
+            job.started_at_ms = Some(now_ms());
+        }
+
+        Ok(true)
+    }
+
+    fn release_slot(&self) {
+        if let Ok(mut state) = self.lock() {
+            state.running = state.running.saturating_sub(1);
+        }
+        self.slot_freed.notify_waiters();
+    }
+}
+
+/// A registered job. Dropping it before `finish` marks the job as failed.
+pub struct JobHandle {
+    job_id: String,
+    holds_slot: bool,
+    finished: bool,
+}
+
+impl JobHandle {
+    pub fn id(&self) -> &str {
+        &self.job_id
+    }
+
+    /// Per-job scratch folder, so parallel jobs never share temp files
+    pub fn temp_dir(&self, app_data_dir: &Path) -> PathBuf {
+        app_data_dir.join("jobs").join(&self.job_id)
+    }
+
+    /// Wait until the job is allowed to run (max concurrency)
+    pub async fn wait_for_slot(&mut self) -> Result<()> {
+        loop {
+            // Register for the wake-up before checking, so a slot freed in
+            // between isn't missed
+            let slot_freed = JOB_MANAGER.slot_freed.notified();
+
+            if JOB_MANAGER.try_take_slot(&self.job_id)? {
+                self.holds_slot = true;
+                debug!("Job {} started", self.job_id);
+                return Ok(());
+            }
+
+            debug!("Job {} queued, waiting for a free slot", self.job_id);
+            slot_freed.await;
+        }
+    }
+
+    /// Mark the job as completed or failed and free its slot
+    pub fn finish<T>(mut self, result: &Result<T>) {
+        let error = result.as_ref().err().map(|e| format!("{:#}", e));
+        match &error {
+            Some(e) => warn!("Job {} failed: {}", self.job_id, e),
+            None => info!("Job {} completed", self.job_id),
+        }
+
+        JOB_MANAGER.update_job(&self.job_id, |job| {
+            job.status = if error.is_some() {
+                JobStatus::Failed
+            } else {
+                JobStatus::Completed
+            };
+            job.error = error;
+            job.finished_at_ms = Some(now_ms());
+        });
+
+        self.finished = true;
+    }
+}
+
+impl Drop for JobHandle {
+    fn drop(&mut self) {
+        if !self.finished {
+            JOB_MANAGER.update_job(&self.job_id, |job| {
+                job.status = JobStatus::Failed;
+                job.error = Some("Job was cancelled".to_string());
+                job.finished_at_ms = Some(now_ms());
+            });
+        }
+
+        if self.holds_slot {
+            JOB_MANAGER.release_slot();
+        }
+    }
+}
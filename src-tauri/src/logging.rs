@@ -0,0 +1,186 @@
+use anyhow::{Context, Result};
+use once_cell::sync::{Lazy, OnceCell};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::layer::{Context as LayerContext, SubscriberExt};
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{reload, Layer, Registry};
+
+/// How many log entries are kept in memory for `get_recent_logs`
+const MAX_BUFFERED_LOGS: usize = 2000;
+
+/// How many daily log files are kept on disk before the oldest is deleted
+const MAX_LOG_FILES: usize = 7;
+
+/// A single log entry as returned to the frontend
+#[derive(Debug, Clone, Serialize)]
+pub struct LogEntry {
+    pub timestamp_ms: u64, // Unix epoch milliseconds
+    pub level: String,     // "ERROR", "WARN", "INFO", "DEBUG", "TRACE"
+    pub target: String,    // Rust module path that emitted the log
+    pub message: String,
+}
+
+struct BufferedLog {
+    level: Level,
+    entry: LogEntry,
+}
+
+// Ring buffer of the most recent log entries (debug console / bug reports)
+static RECENT_LOGS: Lazy<Mutex<VecDeque<BufferedLog>>> =
+    Lazy::new(|| Mutex::new(VecDeque::with_capacity(MAX_BUFFERED_LOGS)));
+
+// Handle used to change the log level at runtime
+static LEVEL_HANDLE: OnceCell<reload::Handle<LevelFilter, Registry>> = OnceCell::new();
+
+// Keeps the non-blocking file writer alive (logs are flushed when dropped)
+static FILE_WRITER_GUARD: OnceCell<WorkerGuard> = OnceCell::new();
+
+/// Collects the formatted message and extra fields of an event
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+    fields: Vec<String>,
+}
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{:?}", value);
+        } else {
+            self.fields.push(format!("{}={:?}", field.name(), value));
+        }
+    }
+}
+
+/// Layer pushing every event into `RECENT_LOGS`
+struct RecentLogsLayer;
+
+impl<S: Subscriber> Layer<S> for RecentLogsLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: LayerContext<'_, S>) {
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+
+        let mut message = visitor.message;
+        if !visitor.fields.is_empty() {
+            message.push(' ');
+            message.push_str(&visitor.fields.join(" "));
+        }
+
+        let metadata = event.metadata();
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+
+        if let Ok(mut logs) = RECENT_LOGS.lock() {
+            if logs.len() >= MAX_BUFFERED_LOGS {
+                logs.pop_front();
+            }
+            logs.push_back(BufferedLog {
+                level: *metadata.level(),
+                entry: LogEntry {
+                    timestamp_ms,
+                    level: metadata.level().to_string(),
+                    target: metadata.target().to_string(),
+                    message,
+                },
+            });
+        }
+    }
+}
+
+fn default_level() -> LevelFilter {
+    if cfg!(debug_assertions) {
+        LevelFilter::DEBUG
+    } else {
+        LevelFilter::INFO
+    }
+}
+
+/// Parse a level name ("error", "warn", "info", "debug", "trace", "off")
+pub fn parse_level(level: &str) -> Result<LevelFilter> {
+    level
+        .trim()
+        .parse::<LevelFilter>()
+        .map_err(|_| anyhow::anyhow!("Invalid log level: '{}'", level))
+}
+
+/// Install the global tracing subscriber.
+///
+/// Logs go to:
+/// - stdout (visible in `tauri dev`)
+/// - daily-rotated files in `log_dir` (kept for `MAX_LOG_FILES` days)
+/// - an in-memory ring buffer exposed through `recent_logs`
+pub fn init_logging(log_dir: &Path) -> Result<()> {
+    fs::create_dir_all(log_dir).context("Failed to create log directory")?;
+
+    let file_appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix("whisperer")
+        .filename_suffix("log")
+        .max_log_files(MAX_LOG_FILES)
+        .build(log_dir)
+        .context("Failed to create log file appender")?;
+    let (file_writer, guard) = tracing_appender::non_blocking(file_appender);
+
+    let (level_layer, level_handle) = reload::Layer::new(default_level());
+
+    tracing_subscriber::registry()
+        .with(level_layer)
+        .with(tracing_subscriber::fmt::layer())
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_ansi(false)
+                .with_writer(file_writer),
+        )
+        .with(RecentLogsLayer)
+        .try_init()
+        .context("Failed to install tracing subscriber")?;
+
+    let _ = FILE_WRITER_GUARD.set(guard);
+    let _ = LEVEL_HANDLE.set(level_handle);
+
+    Ok(())
+}
+
+/// Change the active log level at runtime
+pub fn set_level(level: LevelFilter) -> Result<()> {
+    let handle = LEVEL_HANDLE
+        .get()
+        .context("Logging has not been initialized")?;
+
+    handle
+        .modify(|filter| *filter = level)
+        .context("Failed to update log level")?;
+
+    Ok(())
+}
+
+/// Get the most recent buffered logs at `min_level` or more severe (oldest first)
+pub fn recent_logs(min_level: LevelFilter, limit: usize) -> Result<Vec<LogEntry>> {
+    let logs = RECENT_LOGS
+        .lock()
+        .map_err(|e| anyhow::anyhow!("Failed to lock log buffer: {}", e))?;
+
+    let mut entries: Vec<LogEntry> = logs
+        .iter()
+        .rev()
+        .filter(|log| log.level <= min_level)
+        .take(limit)
+        .map(|log| log.entry.clone())
+        .collect();
+    entries.reverse();
+
+    Ok(entries)
+}
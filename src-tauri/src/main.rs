@@ -2,34 +2,102 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 use anyhow::{Context, Result};
+use api_server::{ApiServerInfo, DEFAULT_API_PORT};
+use caption_server::{CaptionServerInfo, DEFAULT_CAPTION_PORT, WHISPER_LIVE_SESSION_ID};
+use dictation::{DictationSettings, DictationStatus};
+use jobs::{progress_event_name, JobInfo, JOB_MANAGER};
+use logging::LogEntry;
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 use tauri::{AppHandle, Emitter, Manager};
+use tracing::info;
 use whisper_rs::{WhisperContext, WhisperContextParameters};
-use once_cell::sync::Lazy;
 
+mod api_server; // OpenAI-compatible local HTTP API
+mod auth; // access tokens for the local servers
+mod caption_server; // WebSocket live captions (OBS browser source)
+mod cli; // headless `transcribe` subcommand
+mod dictation; // global hotkey push-to-talk dictation
+mod jobs; // concurrent transcription jobs
+mod logging; // tracing setup + in-memory log buffer
+mod pipeline; // file transcription pipeline shared with the CLI
 mod whisper_rs_imp; // tells Rust to load src/whisper_rs_imp/mod.rs
 
 #[cfg(any(target_os = "windows", target_os = "linux"))]
 mod vosk_live_transcriber; // Vosk real-time transcription
 
-use whisper_rs_imp::transcriber::{transcribe_single_pass, TranscriptionSettings};
+use pipeline::audio::extract_audio_window_with_ffmpeg;
+use pipeline::export::{
+    generate_transcript_document as build_transcript_document, TranscriptDocument,
+    TranscriptDocumentOptions, TranscriptSegment,
+};
+use pipeline::glossary::{self, resolve_glossary, Glossary};
+use pipeline::history::{self, ExportFormat, HistoryEntrySummary, RegeneratedSubtitles};
+use pipeline::models::{
+    check_model_requirements as check_model_memory, ensure_model_fits, models_dir_in,
+    whisper_model_path, ModelRequirements,
+};
+use pipeline::subtitles::{
+    adjust_segments, generate_ass, generate_srt, generate_vtt, snap_to_frames,
+    to_subtitle_segments, SubtitleSegment,
+};
+use pipeline::waveform::{extract_waveform, AudioWaveform};
+use pipeline::{run_transcription, TranscriptionProgress, TranscriptionResult};
+use whisper_rs_imp::benchmark::{benchmark_model as run_model_benchmark, BenchmarkResult};
+use whisper_rs_imp::language_detector::{detect_language_from_wav, LanguageDetectionResult};
 use whisper_rs_imp::live_transcriber::{
     transcribe_live_chunk, LiveTranscriptionContext, LiveTranscriptionResult,
 };
+use whisper_rs_imp::transcriber::{transcribe_single_pass, TranscriptionSettings};
+
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+use whisper_rs_imp::live_transcriber::{
+    transcribe_pcm_utterance, translate_pcm_utterance, translation_requested,
+};
 
 #[cfg(any(target_os = "windows", target_os = "linux"))]
 use vosk_live_transcriber::{
-    VoskSessionManager, VoskTranscriptionResult,
+    ClosedVoskSession, RefinedVoskUtterance, TranslatedVoskUtterance, VoskSessionInfo,
+    VoskSessionManager, VoskSessionOptions, VoskTranscriptionResult,
 };
 
 // Global context manager for live transcription (Whisper)
 static LIVE_CONTEXT: Lazy<Arc<Mutex<LiveTranscriptionContext>>> =
     Lazy::new(|| Arc::new(Mutex::new(LiveTranscriptionContext::new())));
 
+// How often idle Vosk sessions are looked for
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+const VOSK_REAPER_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+
+// Whisper model translating Vosk results when the session doesn't pick one
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+const DEFAULT_VOSK_TRANSLATION_MODEL: &str = "base";
+
+// Dual-model mode: Whisper refines one finalized Vosk utterance at a time
+// (tokio semaphores are fair, so utterances are refined in order)
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+static VOSK_REFINEMENT_QUEUE: Lazy<tokio::sync::Semaphore> =
+    Lazy::new(|| tokio::sync::Semaphore::new(1));
+
+// Same for translating finalized Vosk utterances
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+static VOSK_TRANSLATION_QUEUE: Lazy<tokio::sync::Semaphore> =
+    Lazy::new(|| tokio::sync::Semaphore::new(1));
+
+// Whisper models of the refinement and translation queues, kept loaded between
+// utterances without evicting each other or the live transcription model
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+static VOSK_REFINEMENT_CONTEXT: Lazy<Arc<Mutex<LiveTranscriptionContext>>> =
+    Lazy::new(|| Arc::new(Mutex::new(LiveTranscriptionContext::new())));
+
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+static VOSK_TRANSLATION_CONTEXT: Lazy<Arc<Mutex<LiveTranscriptionContext>>> =
+    Lazy::new(|| Arc::new(Mutex::new(LiveTranscriptionContext::new())));
+
 // Global session manager for Vosk
 #[cfg(any(target_os = "windows", target_os = "linux"))]
 static VOSK_SESSION_MANAGER: Lazy<Arc<Mutex<VoskSessionManager>>> =
@@ -39,43 +107,73 @@ static VOSK_SESSION_MANAGER: Lazy<Arc<Mutex<VoskSessionManager>>> =
 // TYPES & STRUCTURES
 // ============================================================================
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct SubtitleSegment {
-    index: usize,
-    start_time: f64,
-    end_time: f64,
-    text: String,
-}
-
 #[derive(Debug, Clone, Serialize)]
 #[serde(tag = "type")]
-enum TranscriptionProgress {
-    #[serde(rename = "converting")]
-    Converting { message: String },
-
-    #[serde(rename = "detecting_language")]
-    DetectingLanguage,
-
-    #[serde(rename = "language_detected")]
-    LanguageDetected { language: String },
+enum BenchmarkProgress {
+    #[serde(rename = "started")]
+    Started {
+        model_name: String,
+        index: usize,
+        total: usize,
+    },
+
+    #[serde(rename = "completed")]
+    Completed {
+        index: usize,
+        total: usize,
+        result: BenchmarkResult,
+    },
+
+    #[serde(rename = "failed")]
+    Failed {
+        model_name: String,
+        index: usize,
+        total: usize,
+        error: String,
+    },
+}
 
-    #[serde(rename = "transcribing")]
-    Transcribing { progress: u8 },
+/// How a file (or a range of it) is transcribed
+/// Also the `options` of `transcribe_range`, where omitted fields use the defaults
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+struct TranscribeOptions {
+    auto_detect_language: bool, // ignored when `settings.language` is set
+    settings: Option<TranscriptionSettings>,
+    job_id: Option<String>, // generated when omitted
+    ignore_memory_check: bool,
+    /// Keep the result so its segments can be edited (whole files only,
+    /// not API server uploads which are deleted right away)
+    #[serde(skip)]
+    save_to_history: bool,
+}
 
-    #[serde(rename = "generating_subtitles")]
-    GeneratingSubtitles,
+impl Default for TranscribeOptions {
+    fn default() -> Self {
+        Self {
+            auto_detect_language: true,
+            settings: None,
+            job_id: None,
+            ignore_memory_check: false,
+            save_to_history: false,
+        }
+    }
+}
 
-    #[serde(rename = "complete")]
-    Complete { subtitle_format: String },
+#[derive(Debug, Serialize)]
+struct TranscriptionRangeResult {
+    language: String,
+    start_sec: f64,
+    end_sec: f64,
+    segments: Vec<SubtitleSegment>, // timestamps are on the original file timeline
 }
 
 #[derive(Debug, Serialize)]
-struct TranscriptionResult {
-    text: String,
+struct AdjustedSubtitles {
+    segments: Vec<SubtitleSegment>,
     subtitles_srt: String,
     subtitles_vtt: String,
-    language: String,
-    segments: Vec<SubtitleSegment>,
+    subtitles_ass: String,
 }
 
 // ============================================================================
@@ -84,26 +182,65 @@ struct TranscriptionResult {
 
 #[cfg(any(target_os = "windows", target_os = "linux"))]
 /// Start a new Vosk live transcription session
+/// `options` enables speaker identification, alternatives, word timings,
+/// translation of final results (`target_language`, done by a Whisper model)
+/// and the dual-model mode (`refine_model_name`, see `process_vosk_chunk`)
 /// Returns session ID to use in subsequent chunk calls
 #[tauri::command]
 async fn start_vosk_session(
     app: AppHandle,
     model_name: String,
     sample_rate: f32,
+    options: Option<VoskSessionOptions>,
 ) -> Result<String, String> {
+    let options = options.unwrap_or_default();
     let models_dir = get_models_dir_internal(&app).map_err(|e| format!("{:#}", e))?;
     let model_path = models_dir.join(&model_name);
 
     if !model_path.exists() {
-        return Err(format!("Vosk model '{}' not found. Please download it first.", model_name));
+        return Err(format!(
+            "Vosk model '{}' not found. Please download it first.",
+            model_name
+        ));
+    }
+
+    let speaker_model_path = match &options.speaker_model_name {
+        Some(speaker_model_name) => {
+            let path = models_dir.join(speaker_model_name);
+            if !path.exists() {
+                return Err(format!(
+                    "Vosk speaker model '{}' not found. Please download it first.",
+                    speaker_model_name
+                ));
+            }
+            Some(path)
+        }
+        None => None,
+    };
+
+    // Fail now rather than on the first final result
+    if let Some(refine_model_name) = &options.refine_model_name {
+        whisper_model_path(&models_dir, refine_model_name).map_err(|e| format!("{:#}", e))?;
+    }
+    if options.is_translating() {
+        let translation_model_path =
+            vosk_translation_model_path(&models_dir, &options).map_err(|e| format!("{:#}", e))?;
+        translation_requested(options.target_language.as_deref(), &translation_model_path)
+            .map_err(|e| format!("{:#}", e))?;
     }
 
     // Create session in blocking task
     let session_id = tokio::task::spawn_blocking(move || {
-        let mut manager = VOSK_SESSION_MANAGER.lock()
+        let mut manager = VOSK_SESSION_MANAGER
+            .lock()
             .map_err(|e| anyhow::anyhow!("Failed to lock session manager: {}", e))?;
 
-        manager.start_session(&model_path, sample_rate)
+        manager.start_session(
+            &model_path,
+            sample_rate,
+            options,
+            speaker_model_path.as_ref(),
+        )
     })
     .await
     .map_err(|e| format!("Failed to spawn task: {}", e))?
@@ -112,58 +249,356 @@ async fn start_vosk_session(
     Ok(session_id)
 }
 
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+/// Whisper model file used to translate a Vosk session's results
+fn vosk_translation_model_path(
+    models_dir: &std::path::Path,
+    options: &VoskSessionOptions,
+) -> Result<PathBuf> {
+    let model_name = options
+        .translation_model_name
+        .as_deref()
+        .or(options.refine_model_name.as_deref())
+        .unwrap_or(DEFAULT_VOSK_TRANSLATION_MODEL);
+
+    whisper_model_path(models_dir, model_name)
+}
+
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+/// Get the model and options of an active Vosk session
+#[tauri::command]
+fn get_vosk_session_info(session_id: String) -> Result<VoskSessionInfo, String> {
+    let manager = VOSK_SESSION_MANAGER
+        .lock()
+        .map_err(|e| format!("Failed to lock session manager: {}", e))?;
+
+    manager
+        .session_info(&session_id)
+        .map_err(|e| format!("{:#}", e))
+}
+
 #[cfg(any(target_os = "windows", target_os = "linux"))]
 /// Process audio chunk in existing Vosk session
 /// Returns transcription result (partial or final)
+///
+/// When the session has a target language, final results are queued to Whisper
+/// and their translation is emitted as "vosk-utterance-translated" events with the
+/// same `utterance_id`. In dual-model mode, final results are also queued to Whisper
+/// and re-emitted as "vosk-utterance-refined" events
 #[tauri::command]
 async fn process_vosk_chunk(
+    app: AppHandle,
     session_id: String,
     pcm_audio: Vec<i16>,
 ) -> Result<VoskTranscriptionResult, String> {
     // Process chunk in blocking task
-    let result = tokio::task::spawn_blocking(move || {
-        let mut manager = VOSK_SESSION_MANAGER.lock()
-            .map_err(|e| anyhow::anyhow!("Failed to lock session manager: {}", e))?;
-
-        manager.process_chunk(&session_id, &pcm_audio)
+    let (result, finished_utterance) = tokio::task::spawn_blocking({
+        let session_id = session_id.clone();
+        move || -> Result<(VoskTranscriptionResult, Option<(Vec<i16>, VoskSessionInfo)>)> {
+            let mut manager = VOSK_SESSION_MANAGER
+                .lock()
+                .map_err(|e| anyhow::anyhow!("Failed to lock session manager: {}", e))?;
+
+            let result = manager.process_chunk(&session_id, &pcm_audio)?;
+            let finished_utterance = match manager.take_finished_utterance(&session_id) {
+                Some(utterance) => Some((utterance, manager.session_info(&session_id)?)),
+                None => None,
+            };
+
+            Ok((result, finished_utterance))
+        }
     })
     .await
     .map_err(|e| format!("Failed to spawn task: {}", e))?
     .map_err(|e| format!("Vosk chunk processing failed: {:#}", e))?;
 
+    // Whisper runs in the background, the Vosk result is returned right away
+    if let Some((utterance, info)) = finished_utterance {
+        let models_dir = get_models_dir_internal(&app).map_err(|e| format!("{:#}", e))?;
+
+        if info.options.is_translating() {
+            spawn_utterance_translation(
+                app.clone(),
+                session_id.clone(),
+                &result,
+                utterance.clone(),
+                &info,
+                &models_dir,
+            );
+        }
+
+        if let Some(refine_model_name) = &info.options.refine_model_name {
+            let model_path = whisper_model_path(&models_dir, refine_model_name)
+                .map_err(|e| format!("{:#}", e))?;
+            spawn_utterance_refinement(
+                app.clone(),
+                session_id.clone(),
+                &result,
+                utterance,
+                info.sample_rate,
+                model_path,
+                info.options.ignore_memory_check,
+            );
+        }
+    }
+
+    caption_server::publish_caption(&session_id, &result.text, result.is_partial);
+
     Ok(result)
 }
 
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+/// Queue a finalized Vosk utterance for translation and emit "vosk-utterance-translated"
+/// once it's done (with an `error` if Whisper failed), the translated caption is
+/// published to the caption server
+fn spawn_utterance_translation(
+    app: AppHandle,
+    session_id: String,
+    vosk_result: &VoskTranscriptionResult,
+    utterance: Vec<i16>,
+    info: &VoskSessionInfo,
+    models_dir: &std::path::Path,
+) {
+    let utterance_id = vosk_result.utterance_id;
+    let original_text = vosk_result.text.clone();
+    let sample_rate = info.sample_rate;
+    let ignore_memory_check = info.options.ignore_memory_check;
+    let model_path = vosk_translation_model_path(models_dir, &info.options);
+
+    tauri::async_runtime::spawn(async move {
+        let Ok(_permit) = VOSK_TRANSLATION_QUEUE.acquire().await else {
+            return;
+        };
+
+        let translation = tokio::task::spawn_blocking(move || {
+            translate_pcm_utterance(
+                &utterance,
+                sample_rate,
+                &VOSK_TRANSLATION_CONTEXT,
+                &model_path?,
+                ignore_memory_check,
+            )
+        })
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to spawn task: {}", e))
+        .and_then(|result| result);
+
+        let translated = match translation {
+            Ok(translated_text) => {
+                caption_server::publish_translated_caption(
+                    &session_id,
+                    &original_text,
+                    Some(&translated_text),
+                    false,
+                );
+                TranslatedVoskUtterance {
+                    session_id,
+                    utterance_id,
+                    original_text,
+                    translated_text: Some(translated_text),
+                    error: None,
+                }
+            }
+            // The captions keep going with the original text
+            Err(e) => {
+                tracing::warn!(
+                    "Translating utterance {} of {} failed: {:#}",
+                    utterance_id,
+                    session_id,
+                    e
+                );
+                TranslatedVoskUtterance {
+                    session_id,
+                    utterance_id,
+                    original_text,
+                    translated_text: None,
+                    error: Some(format!("{:#}", e)),
+                }
+            }
+        };
+
+        app.emit("vosk-utterance-translated", translated).ok();
+    });
+}
+
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+/// Queue a finalized Vosk utterance for Whisper and emit "vosk-utterance-refined"
+/// once it's done (with Vosk's text and an `error` if Whisper failed)
+fn spawn_utterance_refinement(
+    app: AppHandle,
+    session_id: String,
+    vosk_result: &VoskTranscriptionResult,
+    utterance: Vec<i16>,
+    sample_rate: f32,
+    model_path: PathBuf,
+    ignore_memory_check: bool,
+) {
+    let utterance_id = vosk_result.utterance_id;
+    let original_text = vosk_result.text.clone();
+
+    tauri::async_runtime::spawn(async move {
+        let Ok(_permit) = VOSK_REFINEMENT_QUEUE.acquire().await else {
+            return;
+        };
+
+        let refined = tokio::task::spawn_blocking(move || {
+            transcribe_pcm_utterance(
+                &utterance,
+                sample_rate,
+                &VOSK_REFINEMENT_CONTEXT,
+                &model_path,
+                ignore_memory_check,
+            )
+        })
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to spawn task: {}", e))
+        .and_then(|result| result);
+
+        let refined = match refined {
+            Ok(result) => RefinedVoskUtterance {
+                session_id,
+                utterance_id,
+                original_text,
+                text: result.text,
+                language: Some(result.language),
+                error: None,
+            },
+            Err(e) => {
+                tracing::warn!(
+                    "Refining utterance {} of {} failed: {:#}",
+                    utterance_id,
+                    session_id,
+                    e
+                );
+                RefinedVoskUtterance {
+                    session_id,
+                    utterance_id,
+                    text: original_text.clone(),
+                    original_text,
+                    language: None,
+                    error: Some(format!("{:#}", e)),
+                }
+            }
+        };
+
+        app.emit("vosk-utterance-refined", refined).ok();
+    });
+}
+
 #[cfg(any(target_os = "windows", target_os = "linux"))]
 /// End Vosk session and get final transcription
 #[tauri::command]
-async fn end_vosk_session(
-    session_id: String,
-) -> Result<String, String> {
+async fn end_vosk_session(session_id: String) -> Result<String, String> {
     // End session in blocking task
-    let final_text = tokio::task::spawn_blocking(move || {
-        let mut manager = VOSK_SESSION_MANAGER.lock()
-            .map_err(|e| anyhow::anyhow!("Failed to lock session manager: {}", e))?;
-
-        manager.end_session(&session_id)
+    let final_text = tokio::task::spawn_blocking({
+        let session_id = session_id.clone();
+        move || {
+            let mut manager = VOSK_SESSION_MANAGER
+                .lock()
+                .map_err(|e| anyhow::anyhow!("Failed to lock session manager: {}", e))?;
+
+            manager.end_session(&session_id)
+        }
     })
     .await
     .map_err(|e| format!("Failed to spawn task: {}", e))?
     .map_err(|e| format!("Failed to end Vosk session: {:#}", e))?;
 
+    caption_server::publish_caption(&session_id, &final_text, false);
+
     Ok(final_text)
 }
 
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+/// List active Vosk sessions (with their idle time)
+#[tauri::command]
+fn list_vosk_sessions() -> Result<Vec<VoskSessionInfo>, String> {
+    let manager = VOSK_SESSION_MANAGER
+        .lock()
+        .map_err(|e| format!("Failed to lock session manager: {}", e))?;
+
+    Ok(manager.list_sessions())
+}
+
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+/// Finalize and drop every Vosk session (frees the loaded models)
+/// Returns the closed sessions with their final text
+#[tauri::command]
+async fn force_close_all_sessions() -> Result<Vec<ClosedVoskSession>, String> {
+    tokio::task::spawn_blocking(|| {
+        let mut manager = VOSK_SESSION_MANAGER
+            .lock()
+            .map_err(|e| format!("Failed to lock session manager: {}", e))?;
+
+        Ok(manager.close_all_sessions())
+    })
+    .await
+    .map_err(|e| format!("Failed to spawn task: {}", e))?
+}
+
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+/// Change how long a Vosk session may stay without audio before it's closed
+#[tauri::command]
+fn set_vosk_idle_timeout(seconds: u64) -> Result<(), String> {
+    let mut manager = VOSK_SESSION_MANAGER
+        .lock()
+        .map_err(|e| format!("Failed to lock session manager: {}", e))?;
+
+    manager
+        .set_idle_timeout(std::time::Duration::from_secs(seconds))
+        .map_err(|e| format!("{:#}", e))
+}
+
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+#[tauri::command]
+fn get_vosk_idle_timeout() -> Result<u64, String> {
+    let manager = VOSK_SESSION_MANAGER
+        .lock()
+        .map_err(|e| format!("Failed to lock session manager: {}", e))?;
+
+    Ok(manager.idle_timeout().as_secs())
+}
+
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+/// Periodically close Vosk sessions the frontend forgot about (e.g. after a crash)
+/// Emits "vosk-session-expired" for each closed session
+fn spawn_vosk_session_reaper(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(VOSK_REAPER_INTERVAL);
+
+        loop {
+            interval.tick().await;
+
+            let expired = tokio::task::spawn_blocking(|| {
+                VOSK_SESSION_MANAGER
+                    .lock()
+                    .map(|mut manager| manager.expire_idle_sessions())
+                    .unwrap_or_default()
+            })
+            .await
+            .unwrap_or_default();
+
+            for session in expired {
+                caption_server::publish_caption(&session.session_id, &session.final_text, false);
+                app.emit("vosk-session-expired", session).ok();
+            }
+        }
+    });
+}
+
 // ============================================================================
 // LIVE TRANSCRIPTION COMMANDS - WHISPER (LEGACY)
 // ============================================================================
 
 /// Whisper live transcription (SLOW, high-quality)
+/// `target_language` ("en") adds a translate pass, returned as `translated_text`
 #[tauri::command]
 async fn transcribe_audio_chunk(
     app: AppHandle,
     audio_data: Vec<u8>,
     model_name: Option<String>,
+    target_language: Option<String>,
+    ignore_memory_check: Option<bool>,
 ) -> Result<LiveTranscriptionResult, String> {
     let model = model_name.unwrap_or_else(|| "tiny".to_string());
 
@@ -172,257 +607,696 @@ async fn transcribe_audio_chunk(
     let model_path = models_dir.join(format!("ggml-{}.bin", model));
 
     if !model_path.exists() {
-        return Err(format!("Model '{}' not found. Please download it first.", model));
+        return Err(format!(
+            "Model '{}' not found. Please download it first.",
+            model
+        ));
     }
 
     // Run transcription in blocking task
     let result = tokio::task::spawn_blocking(move || {
-        transcribe_live_chunk(&audio_data, &LIVE_CONTEXT, &model_path)
+        transcribe_live_chunk(
+            &audio_data,
+            &LIVE_CONTEXT,
+            &model_path,
+            target_language.as_deref(),
+            ignore_memory_check.unwrap_or(false),
+        )
     })
     .await
     .map_err(|e| format!("Failed to spawn task: {}", e))?
     .map_err(|e| format!("Transcription failed: {:#}", e))?;
 
+    caption_server::publish_translated_caption(
+        WHISPER_LIVE_SESSION_ID,
+        &result.text,
+        result.translated_text.as_deref(),
+        false,
+    );
+
     Ok(result)
 }
 
 // ============================================================================
-// UTILITY FUNCTIONS
+// MAIN TRANSCRIPTION LOGIC - SINGLE-PASS IMPLEMENTATION
 // ============================================================================
 
-/// Convert audio to 16kHz mono WAV and get duration
-fn convert_audio_with_ffmpeg(input_path: &Path, output_path: &Path) -> Result<f64> {
-    let input_str = input_path.to_str().context("Invalid input path encoding")?;
-    let output_str = output_path
-        .to_str()
-        .context("Invalid output path encoding")?;
+/// Transcribe a whole file as a job
+/// Progress is emitted as "transcription-progress:{job_id}" (`job_id` is generated when omitted)
+#[tauri::command]
+async fn transcribe_file_advanced(
+    app: AppHandle,
+    file_path: String,
+    model_name: Option<String>,
+    detect_language: Option<bool>,
+    settings: Option<TranscriptionSettings>,
+    job_id: Option<String>,
+    ignore_memory_check: Option<bool>,
+) -> Result<TranscriptionResult, String> {
+    let options = TranscribeOptions {
+        auto_detect_language: detect_language.unwrap_or(true),
+        settings,
+        job_id,
+        ignore_memory_check: ignore_memory_check.unwrap_or(false),
+        save_to_history: true,
+    };
+    let result = transcribe_file_advanced_impl(app, file_path, model_name, options).await;
 
-    let duration_output = Command::new("ffprobe")
-        .args([
-            "-v",
-            "error",
-            "-show_entries",
-            "format=duration",
-            "-of",
-            "default=noprint_wrappers=1:nokey=1",
-            input_str,
-        ])
-        .output()
-        .context("Failed to run ffprobe")?;
+    match result {
+        Ok(res) => Ok(res),
+        Err(e) => Err(format!("{:#}", e)),
+    }
+}
 
-    let duration: f64 = String::from_utf8_lossy(&duration_output.stdout)
-        .trim()
-        .parse()
-        .unwrap_or(0.0);
+async fn transcribe_file_advanced_impl(
+    app: AppHandle,
+    file_path: String,
+    model_name: Option<String>,
+    options: TranscribeOptions,
+) -> Result<TranscriptionResult> {
+    let TranscribeOptions {
+        auto_detect_language,
+        settings,
+        job_id,
+        ignore_memory_check,
+        save_to_history,
+    } = options;
+    let model = model_name.unwrap_or_else(|| "base".to_string());
+    let audio_path = PathBuf::from(&file_path);
 
-    // TODO: Use ffmpeg-sidecar to auto-download/bundle ffmpeg instead of relying on system installation
-    // Currently requires user to have ffmpeg installed on their system
-    let status = Command::new("ffmpeg")
-        .args([
-            "-i",
-            input_str,
-            "-ar",
-            "16000",
-            "-ac",
-            "1",
-            "-c:a",
-            "pcm_s16le",
-            "-y",
-            output_str,
-        ])
-        .output()
-        .context("Failed to run ffmpeg")?;
+    let models_dir = get_models_dir_internal(&app)?;
+    let model_path = whisper_model_path(&models_dir, &model)?;
 
-    if !status.status.success() {
-        anyhow::bail!(
-            "ffmpeg conversion failed: {}",
-            String::from_utf8_lossy(&status.stderr)
-        );
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .context("Failed to get app data directory")?;
+
+    // Fail early on unknown glossaries, before waiting for a slot
+    let glossary = resolve_glossary(&app_data_dir, settings.as_ref())?;
+
+    let mut job = JOB_MANAGER.create_job("transcription", &file_path, &model, job_id)?;
+    job.wait_for_slot().await?;
+
+    // Checked once running, so memory used by other running jobs is accounted for
+    if let Err(e) = ensure_model_fits(&model, &model_path, ignore_memory_check) {
+        let result = Err(e);
+        job.finish(&result);
+        return result;
     }
 
-    Ok(duration)
-}
+    let temp_dir = job.temp_dir(&app_data_dir);
+    let job_id = job.id().to_string();
+
+    // Whole pipeline (ffmpeg + Whisper) is blocking
+    let result = tokio::task::spawn_blocking({
+        let temp_dir = temp_dir.clone();
+        let job_id = job_id.clone();
+        move || {
+            run_transcription(
+                &audio_path,
+                &model_path,
+                &temp_dir,
+                auto_detect_language,
+                settings,
+                &glossary,
+                &|progress| {
+                    JOB_MANAGER.report_progress(&job_id, &progress);
+                    app.emit(&progress_event_name(&job_id), progress).ok();
+                },
+            )
+        }
+    })
+    .await
+    .context("Failed to spawn blocking Whisper task")
+    .and_then(|result| result);
+
+    let _ = fs::remove_dir_all(&temp_dir);
+    job.finish(&result);
+
+    // Keep the result so its segments can be edited (result id = job id)
+    let mut result = result?;
+    if save_to_history {
+        match history::save_result(&app_data_dir, &job_id, &file_path, &model, &result) {
+            Ok(()) => result.result_id = Some(job_id),
+            Err(e) => tracing::warn!("Failed to save result {} to history: {:#}", job_id, e),
+        }
+    }
 
-/// Format timestamp for SRT (HH:MM:SS,mmm)
-fn format_timestamp_srt(seconds: f64) -> String {
-    let hours = (seconds / 3600.0).floor() as u32;
-    let minutes = ((seconds % 3600.0) / 60.0).floor() as u32;
-    let secs = (seconds % 60.0).floor() as u32;
-    let millis = ((seconds % 1.0) * 1000.0).floor() as u32;
-    format!("{:02}:{:02}:{:02},{:03}", hours, minutes, secs, millis)
+    Ok(result)
 }
 
-/// Format timestamp for VTT (HH:MM:SS.mmm)
-fn format_timestamp_vtt(seconds: f64) -> String {
-    let hours = (seconds / 3600.0).floor() as u32;
-    let minutes = ((seconds % 3600.0) / 60.0).floor() as u32;
-    let secs = (seconds % 60.0).floor() as u32;
-    let millis = ((seconds % 1.0) * 1000.0).floor() as u32;
-    format!("{:02}:{:02}:{:02}.{:03}", hours, minutes, secs, millis)
+/// Re-transcribe only [start_sec, end_sec] of a file (e.g. with different settings)
+/// Returned segments are offset back to the original timeline so the frontend can splice them in
+/// Runs as a job, progress is emitted as "transcription-progress:{job_id}"
+/// `options` sets language detection, settings, the job id and the memory check override
+#[tauri::command]
+async fn transcribe_range(
+    app: AppHandle,
+    file_path: String,
+    start_sec: f64,
+    end_sec: f64,
+    model_name: Option<String>,
+    options: Option<TranscribeOptions>,
+) -> Result<TranscriptionRangeResult, String> {
+    transcribe_range_impl(
+        app,
+        file_path,
+        start_sec,
+        end_sec,
+        model_name,
+        options.unwrap_or_default(),
+    )
+    .await
+    .map_err(|e| format!("{:#}", e))
 }
 
-/// Generate SRT subtitle format
-fn generate_srt(segments: &[SubtitleSegment]) -> String {
-    let mut srt = String::new();
-    for segment in segments {
-        srt.push_str(&format!("{}\n", segment.index + 1));
-        srt.push_str(&format!(
-            "{} --> {}\n",
-            format_timestamp_srt(segment.start_time),
-            format_timestamp_srt(segment.end_time)
-        ));
-        srt.push_str(&format!("{}\n\n", segment.text.trim()));
+async fn transcribe_range_impl(
+    app: AppHandle,
+    file_path: String,
+    start_sec: f64,
+    end_sec: f64,
+    model_name: Option<String>,
+    options: TranscribeOptions,
+) -> Result<TranscriptionRangeResult> {
+    // Ranges aren't saved to the history (`save_to_history` is ignored)
+    let TranscribeOptions {
+        auto_detect_language,
+        settings,
+        job_id,
+        ignore_memory_check,
+        ..
+    } = options;
+
+    if !start_sec.is_finite() || !end_sec.is_finite() || start_sec < 0.0 {
+        anyhow::bail!("Invalid range: {} - {}", start_sec, end_sec);
+    }
+    if end_sec <= start_sec {
+        anyhow::bail!(
+            "Range end ({:.3}s) must be after range start ({:.3}s)",
+            end_sec,
+            start_sec
+        );
     }
-    srt
+
+    let model = model_name.unwrap_or_else(|| "base".to_string());
+    let audio_path = PathBuf::from(&file_path);
+
+    if !audio_path.exists() {
+        anyhow::bail!("File not found: {}", file_path);
+    }
+
+    let models_dir = get_models_dir_internal(&app)?;
+    let model_path = whisper_model_path(&models_dir, &model)?;
+
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .context("Failed to get app data directory")?;
+
+    let glossary = resolve_glossary(&app_data_dir, settings.as_ref())?;
+
+    let mut job = JOB_MANAGER.create_job("range", &file_path, &model, job_id)?;
+    job.wait_for_slot().await?;
+
+    let temp_dir = job.temp_dir(&app_data_dir);
+    let event_name = progress_event_name(job.id());
+    let emit_progress = |progress: TranscriptionProgress| {
+        JOB_MANAGER.report_progress(job.id(), &progress);
+        app.emit(&event_name, progress).ok();
+    };
+
+    let result: Result<TranscriptionRangeResult> = async {
+        ensure_model_fits(&model, &model_path, ignore_memory_check)?;
+
+        fs::create_dir_all(&temp_dir).context("Failed to create temp directory")?;
+        let temp_wav = temp_dir.join("temp_audio_range.wav");
+
+        // Step 1: Extract only the requested window
+        emit_progress(TranscriptionProgress::Converting {
+            message: format!("Extracting {:.1}s - {:.1}s...", start_sec, end_sec),
+        });
+
+        let preprocessing = settings.as_ref().and_then(|s| s.preprocessing.clone());
+        let preprocessing_report = extract_audio_window_with_ffmpeg(
+            &audio_path,
+            &temp_wav,
+            start_sec,
+            Some(end_sec - start_sec),
+            preprocessing.as_ref(),
+        )?;
+
+        if let Some(report) = preprocessing_report {
+            emit_progress(TranscriptionProgress::Preprocessed { report });
+        }
+
+        // Step 2: Transcribe the window
+        emit_progress(TranscriptionProgress::Transcribing { progress: 50 });
+
+        let settings = glossary.apply_to_settings(settings);
+        let (language, mut segments) = tokio::task::spawn_blocking(move || {
+            transcribe_single_pass(&model_path, &temp_wav, auto_detect_language, settings)
+        })
+        .await
+        .context("Failed to spawn blocking Whisper task")??;
+        glossary.correct_segments(&mut segments);
+
+        // Step 3: Shift back to the original timeline and keep segments inside the window
+        // (Whisper may overshoot the window end, segments starting past it are dropped)
+        let segments = to_subtitle_segments(&segments, start_sec)
+            .into_iter()
+            .filter(|segment| segment.start_time < end_sec)
+            .map(|mut segment| {
+                segment.start_time = segment.start_time.max(start_sec);
+                segment.end_time = segment.end_time.clamp(segment.start_time, end_sec);
+                segment
+            })
+            .collect();
+
+        emit_progress(TranscriptionProgress::Complete {
+            subtitle_format: "segments".to_string(),
+        });
+
+        Ok(TranscriptionRangeResult {
+            language,
+            start_sec,
+            end_sec,
+            segments,
+        })
+    }
+    .await;
+
+    // Removes the extracted WAV too, even when transcription failed
+    let _ = fs::remove_dir_all(&temp_dir);
+    job.finish(&result);
+
+    result
 }
 
-/// Generate WebVTT subtitle format
-fn generate_vtt(segments: &[SubtitleSegment]) -> String {
-    let mut vtt = String::from("WEBVTT\n\n");
-    for segment in segments {
-        vtt.push_str(&format!(
-            "{} --> {}\n",
-            format_timestamp_vtt(segment.start_time),
-            format_timestamp_vtt(segment.end_time)
-        ));
-        vtt.push_str(&format!("{}\n\n", segment.text.trim()));
+// ============================================================================
+// SUBTITLE TIMING
+// ============================================================================
+
+/// Shift (`offset_seconds`) and linearly rescale (`scale`) subtitle timestamps
+/// without re-transcribing, optionally snapping them to `frame_rate` frames
+/// Returns the adjusted segments and regenerated SRT/VTT/ASS files
+#[tauri::command]
+fn adjust_subtitles(
+    segments: Vec<SubtitleSegment>,
+    offset_seconds: Option<f64>,
+    scale: Option<f64>,
+    frame_rate: Option<f64>,
+) -> Result<AdjustedSubtitles, String> {
+    let mut adjusted = adjust_segments(
+        &segments,
+        offset_seconds.unwrap_or(0.0),
+        scale.unwrap_or(1.0),
+    )
+    .map_err(|e| format!("{:#}", e))?;
+
+    if let Some(frame_rate) = frame_rate {
+        adjusted = snap_to_frames(&adjusted, frame_rate).map_err(|e| format!("{:#}", e))?;
     }
-    vtt
+
+    Ok(AdjustedSubtitles {
+        subtitles_srt: generate_srt(&adjusted),
+        subtitles_vtt: generate_vtt(&adjusted),
+        subtitles_ass: generate_ass(&adjusted),
+        segments: adjusted,
+    })
 }
 
 // ============================================================================
-// MAIN TRANSCRIPTION LOGIC - SINGLE-PASS IMPLEMENTATION
+// WAVEFORM
 // ============================================================================
 
+/// Downsampled peak/RMS levels and silence regions of a file, for drawing a
+/// timeline aligned with the segments (`samples_per_second`: 1 - 1000)
 #[tauri::command]
-async fn transcribe_file_advanced(
+async fn get_audio_waveform(
     app: AppHandle,
     file_path: String,
-    model_name: Option<String>,
-    detect_language: Option<bool>,
-    settings: Option<TranscriptionSettings>,
-) -> Result<TranscriptionResult, String> {
-    let result = transcribe_file_advanced_impl(
-        app,
-        file_path,
-        model_name,
-        detect_language.unwrap_or(true),
-        settings,
+    samples_per_second: u32,
+) -> Result<AudioWaveform, String> {
+    let temp_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join("waveforms");
+
+    // ffmpeg decoding + reading the whole file is blocking
+    tokio::task::spawn_blocking(move || {
+        extract_waveform(&PathBuf::from(&file_path), &temp_dir, samples_per_second)
+    })
+    .await
+    .map_err(|e| format!("Failed to spawn task: {}", e))?
+    .map_err(|e| format!("{:#}", e))
+}
+
+// ============================================================================
+// SEGMENT EDITING (HISTORY)
+// ============================================================================
+
+/// Saved results (most recent first, without their segments)
+/// The oldest results are deleted automatically once the history is full
+#[tauri::command]
+fn list_history(app: AppHandle) -> Result<Vec<HistoryEntrySummary>, String> {
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+
+    history::list_entries(&app_data_dir).map_err(|e| format!("{:#}", e))
+}
+
+/// Delete a saved result (its segments can't be edited afterwards)
+#[tauri::command]
+fn delete_history_entry(app: AppHandle, result_id: String) -> Result<(), String> {
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+
+    history::delete_entry(&app_data_dir, &result_id).map_err(|e| format!("{:#}", e))
+}
+
+/// Edit the text and/or timing of a segment of a saved result
+/// (`result_id` is the job id returned as `result_id` by `transcribe_file_advanced`)
+/// Returns every segment of the result after the edit
+#[tauri::command]
+fn update_segment(
+    app: AppHandle,
+    result_id: String,
+    index: usize,
+    new_text: Option<String>,
+    new_start: Option<f64>,
+    new_end: Option<f64>,
+) -> Result<Vec<SubtitleSegment>, String> {
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+
+    history::update_segment(
+        &app_data_dir,
+        &result_id,
+        index,
+        new_text,
+        new_start,
+        new_end,
     )
-    .await;
+    .map_err(|e| format!("{:#}", e))
+}
 
-    match result {
-        Ok(res) => Ok(res),
-        Err(e) => Err(format!("{:#}", e)),
-    }
+/// Merge segments `first_index..=last_index` of a saved result into one
+#[tauri::command]
+fn merge_segments(
+    app: AppHandle,
+    result_id: String,
+    first_index: usize,
+    last_index: usize,
+) -> Result<Vec<SubtitleSegment>, String> {
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+
+    history::merge_segments(&app_data_dir, &result_id, first_index, last_index)
+        .map_err(|e| format!("{:#}", e))
 }
 
-async fn transcribe_file_advanced_impl(
+/// Split a segment of a saved result at `split_time`, the second part's text
+/// starting at character `text_position`
+#[tauri::command]
+fn split_segment(
+    app: AppHandle,
+    result_id: String,
+    index: usize,
+    split_time: f64,
+    text_position: usize,
+) -> Result<Vec<SubtitleSegment>, String> {
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+
+    history::split_segment(&app_data_dir, &result_id, index, split_time, text_position)
+        .map_err(|e| format!("{:#}", e))
+}
+
+/// Regenerate subtitles of a saved result from its edited segments
+#[tauri::command]
+fn regenerate_subtitles(
+    app: AppHandle,
+    result_id: String,
+    formats: Vec<ExportFormat>,
+) -> Result<RegeneratedSubtitles, String> {
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+
+    history::regenerate_subtitles(&app_data_dir, &result_id, &formats)
+        .map_err(|e| format!("{:#}", e))
+}
+
+// ============================================================================
+// DOCUMENT EXPORT
+// ============================================================================
+
+/// Build a Markdown/HTML transcript (meeting notes) from segments: paragraphs
+/// merged by pause and speaker, chapters from long silences or keywords
+#[tauri::command]
+fn generate_transcript_document(
+    segments: Vec<TranscriptSegment>,
+    options: Option<TranscriptDocumentOptions>,
+) -> Result<TranscriptDocument, String> {
+    build_transcript_document(&segments, &options.unwrap_or_default())
+        .map_err(|e| format!("{:#}", e))
+}
+
+// ============================================================================
+// GLOSSARIES
+// ============================================================================
+
+/// List saved glossaries (terms composed into Whisper's initial prompt)
+#[tauri::command]
+fn list_glossaries(app: AppHandle) -> Result<Vec<Glossary>, String> {
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+
+    glossary::load_glossaries(&app_data_dir).map_err(|e| format!("{:#}", e))
+}
+
+/// Create or update a glossary (matched by name)
+#[tauri::command]
+fn save_glossary(app: AppHandle, glossary: Glossary) -> Result<Glossary, String> {
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+
+    glossary::save_glossary(&app_data_dir, glossary).map_err(|e| format!("{:#}", e))
+}
+
+#[tauri::command]
+fn delete_glossary(app: AppHandle, name: String) -> Result<bool, String> {
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+
+    glossary::delete_glossary(&app_data_dir, &name).map_err(|e| format!("{:#}", e))
+}
+
+// ============================================================================
+// JOBS
+// ============================================================================
+
+/// List queued, running and recently finished transcription jobs (oldest first)
+#[tauri::command]
+fn list_jobs() -> Result<Vec<JobInfo>, String> {
+    JOB_MANAGER.list_jobs().map_err(|e| format!("{:#}", e))
+}
+
+#[tauri::command]
+fn get_job_status(job_id: String) -> Result<JobInfo, String> {
+    JOB_MANAGER
+        .job_status(&job_id)
+        .map_err(|e| format!("{:#}", e))
+}
+
+/// Change how many transcription jobs may run at the same time
+#[tauri::command]
+fn set_max_concurrent_jobs(max_jobs: usize) -> Result<(), String> {
+    JOB_MANAGER
+        .set_max_concurrent_jobs(max_jobs)
+        .map_err(|e| format!("{:#}", e))
+}
+
+#[tauri::command]
+fn get_max_concurrent_jobs() -> Result<usize, String> {
+    JOB_MANAGER
+        .max_concurrent_jobs()
+        .map_err(|e| format!("{:#}", e))
+}
+
+// ============================================================================
+// LANGUAGE DETECTION
+// ============================================================================
+
+/// Whisper only looks at the first 30 seconds when detecting the language
+const LANGUAGE_DETECTION_WINDOW_SEC: f64 = 30.0;
+
+// Unique temp WAV names, several detections can run at once
+static LANGUAGE_DETECTION_COUNTER: AtomicU64 = AtomicU64::new(1);
+
+/// Detect the spoken language of a file without running a full transcription
+/// Returns the top language candidates with their probabilities
+#[tauri::command]
+async fn detect_language(
     app: AppHandle,
     file_path: String,
-    model_name: Option<String>,
-    auto_detect_language: bool,
-    settings: Option<TranscriptionSettings>,
-) -> Result<TranscriptionResult> {
-    let model = model_name.unwrap_or_else(|| "base".to_string());
-    let audio_path = PathBuf::from(&file_path);
+    model_name: String,
+    ignore_memory_check: Option<bool>,
+) -> Result<LanguageDetectionResult, String> {
+    let ignore_memory_check = ignore_memory_check.unwrap_or(false);
 
+    detect_language_impl(app, file_path, model_name, ignore_memory_check)
+        .await
+        .map_err(|e| format!("{:#}", e))
+}
+
+async fn detect_language_impl(
+    app: AppHandle,
+    file_path: String,
+    model_name: String,
+    ignore_memory_check: bool,
+) -> Result<LanguageDetectionResult> {
+    let audio_path = PathBuf::from(&file_path);
     if !audio_path.exists() {
         anyhow::bail!("File not found: {}", file_path);
     }
 
     let models_dir = get_models_dir_internal(&app)?;
-    let model_path = models_dir.join(format!("ggml-{}.bin", model));
-    if !model_path.exists() {
-        anyhow::bail!("Model '{}' not found. Please download it first.", model);
-    }
+    let model_path = whisper_model_path(&models_dir, &model_name)?;
 
     let temp_dir = app
         .path()
         .app_data_dir()
         .context("Failed to get app data directory")?;
     fs::create_dir_all(&temp_dir).context("Failed to create temp directory")?;
-    let temp_wav = temp_dir.join("temp_audio.wav");
-
-    // Step 1: Convert audio to 16kHz mono WAV
-    app.emit(
-        "transcription-progress",
-        TranscriptionProgress::Converting {
-            message: "Converting audio to WAV format...".to_string(),
-        },
-    )
-    .ok();
+    let temp_wav = temp_dir.join(format!(
+        "temp_language_detection-{}.wav",
+        LANGUAGE_DETECTION_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+
+    let result = async {
+        extract_audio_window_with_ffmpeg(
+            &audio_path,
+            &temp_wav,
+            0.0,
+            Some(LANGUAGE_DETECTION_WINDOW_SEC),
+            None,
+        )?;
+
+        tokio::task::spawn_blocking({
+            let temp_wav = temp_wav.clone();
+            move || detect_language_from_wav(&model_path, &temp_wav, 5, ignore_memory_check)
+        })
+        .await
+        .context("Failed to spawn blocking Whisper task")?
+    }
+    .await;
 
-    let _duration = convert_audio_with_ffmpeg(&audio_path, &temp_wav)?;
+    // Removes the extracted WAV even when extraction or detection failed
+    let _ = fs::remove_file(&temp_wav);
 
-    // Step 2: Run single-pass transcription
-    app.emit(
-        "transcription-progress",
-        TranscriptionProgress::Transcribing { progress: 50 },
-    )
-    .ok();
+    result
+}
 
-    let (language, segments) = tokio::task::spawn_blocking({
-        let model_path = model_path.clone();
-        let temp_wav = temp_wav.clone();
-        move || transcribe_single_pass(&model_path, &temp_wav, auto_detect_language, settings)
+// ============================================================================
+// MODEL REQUIREMENTS
+// ============================================================================
+
+/// Estimated RAM needed by a downloaded Whisper model vs. available system memory
+/// Transcription commands refuse models that don't fit unless `ignore_memory_check` is set
+#[tauri::command]
+fn check_model_requirements(
+    app: AppHandle,
+    model_name: String,
+) -> Result<ModelRequirements, String> {
+    let models_dir = get_models_dir_internal(&app).map_err(|e| format!("{:#}", e))?;
+    let model_path =
+        whisper_model_path(&models_dir, &model_name).map_err(|e| format!("{:#}", e))?;
+
+    check_model_memory(&model_name, &model_path).map_err(|e| format!("{:#}", e))
+}
+
+// ============================================================================
+// BENCHMARKING
+// ============================================================================
+
+/// Benchmark a downloaded Whisper model on this machine
+/// Returns load time, realtime factor, peak memory and thread count
+#[tauri::command]
+async fn benchmark_model(
+    app: AppHandle,
+    model_name: String,
+    ignore_memory_check: Option<bool>,
+) -> Result<BenchmarkResult, String> {
+    let models_dir = get_models_dir_internal(&app).map_err(|e| format!("{:#}", e))?;
+    let model_path =
+        whisper_model_path(&models_dir, &model_name).map_err(|e| format!("{:#}", e))?;
+
+    tokio::task::spawn_blocking(move || {
+        run_model_benchmark(
+            &model_name,
+            &model_path,
+            ignore_memory_check.unwrap_or(false),
+        )
     })
     .await
-    .context("Failed to spawn blocking Whisper task")??;
-
-    // Emit language detection result
-    app.emit(
-        "transcription-progress",
-        TranscriptionProgress::LanguageDetected {
-            language: language.clone(),
-        },
-    )
-    .ok();
-
-    // Step 3: Format results
-    app.emit(
-        "transcription-progress",
-        TranscriptionProgress::GeneratingSubtitles,
-    )
-    .ok();
+    .map_err(|e| format!("Failed to spawn task: {}", e))?
+    .map_err(|e| format!("Benchmark failed: {:#}", e))
+}
 
-    let final_segments: Vec<SubtitleSegment> = segments
+/// Benchmark every downloaded Whisper model, one after another
+/// Emits "benchmark-progress" events; failed models are reported but don't stop the run
+/// (models that don't fit in memory fail unless `ignore_memory_check` is set)
+#[tauri::command]
+async fn benchmark_all_installed(
+    app: AppHandle,
+    ignore_memory_check: Option<bool>,
+) -> Result<Vec<BenchmarkResult>, String> {
+    let model_names: Vec<String> = list_downloaded_models(app.clone())?
         .iter()
-        .enumerate()
-        .map(|(idx, (start, end, text))| SubtitleSegment {
-            index: idx,
-            start_time: *start,
-            end_time: *end,
-            text: text.clone(),
+        .filter_map(|file_name| {
+            file_name
+                .strip_prefix("ggml-")
+                .and_then(|name| name.strip_suffix(".bin"))
+                .map(|name| name.to_string())
         })
         .collect();
 
-    let text = final_segments
-        .iter()
-        .map(|s| s.text.clone())
-        .collect::<Vec<_>>()
-        .join(" ");
-    let srt = generate_srt(&final_segments);
-    let vtt = generate_vtt(&final_segments);
-
-    // Step 4: Cleanup
-    let _ = fs::remove_file(&temp_wav);
+    let total = model_names.len();
+    let mut results = Vec::with_capacity(total);
+
+    for (index, model_name) in model_names.into_iter().enumerate() {
+        app.emit(
+            "benchmark-progress",
+            BenchmarkProgress::Started {
+                model_name: model_name.clone(),
+                index,
+                total,
+            },
+        )
+        .ok();
+
+        match benchmark_model(app.clone(), model_name.clone(), ignore_memory_check).await {
+            Ok(result) => {
+                app.emit(
+                    "benchmark-progress",
+                    BenchmarkProgress::Completed {
+                        index,
+                        total,
+                        result: result.clone(),
+                    },
+                )
+                .ok();
+                results.push(result);
+            }
+            Err(error) => {
+                app.emit(
+                    "benchmark-progress",
+                    BenchmarkProgress::Failed {
+                        model_name,
+                        index,
+                        total,
+                        error,
+                    },
+                )
+                .ok();
+            }
+        }
+    }
 
-    app.emit(
-        "transcription-progress",
-        TranscriptionProgress::Complete {
-            subtitle_format: "SRT/VTT".to_string(),
-        },
-    )
-    .ok();
-
-    Ok(TranscriptionResult {
-        text,
-        subtitles_srt: srt,
-        subtitles_vtt: vtt,
-        language,
-        segments: final_segments,
-    })
+    Ok(results)
 }
 
 // ============================================================================
@@ -442,7 +1316,7 @@ async fn download_vosk_model(app: AppHandle, model_name: String) -> Result<Strin
     // Download ZIP from alphacephei.com/vosk/models
     let url = format!("https://alphacephei.com/vosk/models/{}.zip", model_name);
 
-    println!("📥 Downloading Vosk model from: {}", url);
+    info!("Downloading Vosk model from: {}", url);
 
     let response = reqwest::get(&url)
         .await
@@ -458,9 +1332,10 @@ async fn download_vosk_model(app: AppHandle, model_name: String) -> Result<Strin
     fs::write(&temp_zip, bytes).map_err(|e| format!("Failed to save ZIP: {}", e))?;
 
     // Extract ZIP
-    println!("📦 Extracting Vosk model...");
+    info!("Extracting Vosk model...");
     let file = fs::File::open(&temp_zip).map_err(|e| format!("Failed to open ZIP: {}", e))?;
-    let mut archive = zip::ZipArchive::new(file).map_err(|e| format!("Failed to read ZIP: {}", e))?;
+    let mut archive =
+        zip::ZipArchive::new(file).map_err(|e| format!("Failed to read ZIP: {}", e))?;
 
     archive
         .extract(&models_dir)
@@ -469,8 +1344,11 @@ async fn download_vosk_model(app: AppHandle, model_name: String) -> Result<Strin
     // Clean up ZIP file
     let _ = fs::remove_file(&temp_zip);
 
-    println!("✅ Vosk model '{}' downloaded successfully", model_name);
-    Ok(format!("Successfully downloaded Vosk model '{}'", model_name))
+    info!("Vosk model '{}' downloaded successfully", model_name);
+    Ok(format!(
+        "Successfully downloaded Vosk model '{}'",
+        model_name
+    ))
 }
 
 #[cfg(any(target_os = "windows", target_os = "linux"))]
@@ -502,6 +1380,121 @@ fn list_vosk_models(app: AppHandle) -> Result<Vec<String>, String> {
     Ok(models)
 }
 
+// ============================================================================
+// LOCAL HTTP API (OpenAI-compatible)
+// ============================================================================
+
+/// Start the local OpenAI-compatible API server (localhost only, bearer token auth)
+/// A random token is generated when none is provided
+#[tauri::command]
+async fn start_api_server(
+    app: AppHandle,
+    port: Option<u16>,
+    token: Option<String>,
+    default_model: Option<String>,
+) -> Result<ApiServerInfo, String> {
+    api_server::start_api_server(
+        app,
+        port.unwrap_or(DEFAULT_API_PORT),
+        token,
+        default_model.unwrap_or_else(|| "base".to_string()),
+    )
+    .await
+    .map_err(|e| format!("{:#}", e))
+}
+
+/// Stop the local API server
+/// Returns false if it wasn't running
+#[tauri::command]
+fn stop_api_server() -> Result<bool, String> {
+    api_server::stop_api_server().map_err(|e| format!("{:#}", e))
+}
+
+/// Get the running API server info (None if stopped)
+#[tauri::command]
+fn get_api_server_status() -> Result<Option<ApiServerInfo>, String> {
+    api_server::api_server_status().map_err(|e| format!("{:#}", e))
+}
+
+// ============================================================================
+// LIVE CAPTION SERVER (WebSocket)
+// ============================================================================
+
+/// Start the live caption server (browser source page + WebSocket feed)
+/// `session_id` picks the published live session (None = every session)
+#[tauri::command]
+async fn start_caption_server(
+    port: Option<u16>,
+    session_id: Option<String>,
+) -> Result<CaptionServerInfo, String> {
+    caption_server::start_caption_server(port.unwrap_or(DEFAULT_CAPTION_PORT), session_id)
+        .await
+        .map_err(|e| format!("{:#}", e))
+}
+
+/// Stop the live caption server
+/// Returns false if it wasn't running
+#[tauri::command]
+fn stop_caption_server() -> Result<bool, String> {
+    caption_server::stop_caption_server().map_err(|e| format!("{:#}", e))
+}
+
+/// Choose which live session is published to caption clients (None = every session)
+#[tauri::command]
+fn set_caption_session(session_id: Option<String>) -> Result<(), String> {
+    caption_server::set_published_session(session_id).map_err(|e| format!("{:#}", e))
+}
+
+/// Get the running caption server info (None if stopped)
+#[tauri::command]
+fn get_caption_server_status() -> Result<Option<CaptionServerInfo>, String> {
+    caption_server::caption_server_status().map_err(|e| format!("{:#}", e))
+}
+
+// ============================================================================
+// DICTATION
+// ============================================================================
+
+/// Enable push-to-talk dictation on a global shortcut (None = disable)
+/// Transcripts are sent as "dictation-result" events, state changes as "dictation-status"
+#[tauri::command]
+fn set_dictation_settings(
+    app: AppHandle,
+    settings: Option<DictationSettings>,
+) -> Result<DictationStatus, String> {
+    dictation::configure(&app, settings).map_err(|e| format!("{:#}", e))
+}
+
+/// Get the dictation state and settings
+#[tauri::command]
+fn get_dictation_status() -> Result<DictationStatus, String> {
+    dictation::dictation_status().map_err(|e| format!("{:#}", e))
+}
+
+// ============================================================================
+// LOGGING
+// ============================================================================
+
+/// Get the most recent backend logs (oldest first)
+/// `level` is the minimum severity to include ("error", "warn", "info", "debug", "trace")
+#[tauri::command]
+fn get_recent_logs(level: Option<String>, limit: Option<usize>) -> Result<Vec<LogEntry>, String> {
+    let min_level = logging::parse_level(level.as_deref().unwrap_or("trace"))
+        .map_err(|e| format!("{:#}", e))?;
+
+    logging::recent_logs(min_level, limit.unwrap_or(200)).map_err(|e| format!("{:#}", e))
+}
+
+/// Change the backend log level at runtime
+#[tauri::command]
+fn set_log_level(level: String) -> Result<(), String> {
+    let level_filter = logging::parse_level(&level).map_err(|e| format!("{:#}", e))?;
+    logging::set_level(level_filter).map_err(|e| format!("{:#}", e))?;
+
+    info!("Log level set to {}", level_filter);
+    Ok(())
+}
+
 // ============================================================================
 // EXISTING COMMANDS (kept for compatibility)
 // ============================================================================
@@ -547,9 +1540,16 @@ fn detect_gpu() -> Result<GpuInfo> {
 
 #[cfg(target_os = "windows")]
 fn get_gpu_details() -> Result<(Option<String>, Option<String>)> {
+    use std::process::Command;
+
     // Use WMIC to get GPU info on Windows
     let output = Command::new("wmic")
-        .args(["path", "win32_VideoController", "get", "name,AdapterCompatibility"])
+        .args([
+            "path",
+            "win32_VideoController",
+            "get",
+            "name,AdapterCompatibility",
+        ])
         .output()
         .context("Failed to execute wmic command")?;
 
@@ -585,10 +1585,7 @@ fn get_models_dir_internal(app: &AppHandle) -> Result<PathBuf> {
         .app_data_dir()
         .context("Failed to get app data directory")?;
 
-    let models_dir = app_data_dir.join("models");
-    fs::create_dir_all(&models_dir).context("Failed to create models directory")?;
-
-    Ok(models_dir)
+    models_dir_in(&app_data_dir)
 }
 
 #[tauri::command]
@@ -656,7 +1653,11 @@ fn list_downloaded_models(app: AppHandle) -> Result<Vec<String>, String> {
 }
 
 #[tauri::command]
-fn test_whisper(app: AppHandle, model_name: String) -> Result<String, String> {
+fn test_whisper(
+    app: AppHandle,
+    model_name: String,
+    ignore_memory_check: Option<bool>,
+) -> Result<String, String> {
     let models_dir = get_models_dir_internal(&app).map_err(|e| format!("{:#}", e))?;
     let model_path = models_dir.join(format!("ggml-{}.bin", model_name));
 
@@ -667,6 +1668,10 @@ fn test_whisper(app: AppHandle, model_name: String) -> Result<String, String> {
         ));
     }
 
+    let ignore_memory_check = ignore_memory_check.unwrap_or(false);
+    ensure_model_fits(&model_name, &model_path, ignore_memory_check)
+        .map_err(|e| format!("❌ {:#}", e))?;
+
     let model_path_str = model_path
         .to_str()
         .ok_or_else(|| "Invalid model path encoding".to_string())?;
@@ -687,7 +1692,7 @@ async fn transcribe_file(
     file_path: String,
     model_name: Option<String>,
 ) -> Result<String, String> {
-    match transcribe_file_advanced(app, file_path, model_name, Some(true), None).await {
+    match transcribe_file_advanced(app, file_path, model_name, Some(true), None, None, None).await {
         Ok(result) => Ok(result.text),
         Err(e) => Err(e),
     }
@@ -697,10 +1702,35 @@ async fn transcribe_file(
 // ============================================================================
 
 fn main() {
+    // `tauri-whisper-app transcribe ...` runs headless and never opens a window
+    if cli::is_cli_invocation() {
+        std::process::exit(cli::run());
+    }
+
     let mut builder = tauri::Builder::default()
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_dialog::init())
-        .plugin(tauri_plugin_os::init());
+        .plugin(tauri_plugin_os::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(
+            tauri_plugin_global_shortcut::Builder::new()
+                .with_handler(|app, shortcut, event| {
+                    dictation::handle_shortcut(app, shortcut, event.state())
+                })
+                .build(),
+        )
+        .setup(|app| {
+            // Log files live next to the models in the app data directory
+            let log_dir = app.path().app_data_dir()?.join("logs");
+            if let Err(e) = logging::init_logging(&log_dir) {
+                eprintln!("Failed to initialize logging: {:#}", e);
+            }
+
+            #[cfg(any(target_os = "windows", target_os = "linux"))]
+            spawn_vosk_session_reaper(app.handle().clone());
+
+            Ok(())
+        });
 
     // Register platform-agnostic commands
     #[cfg(any(target_os = "windows", target_os = "linux"))]
@@ -708,6 +1738,8 @@ fn main() {
         builder = builder.invoke_handler(tauri::generate_handler![
             hello_world,
             get_gpu_info,
+            get_recent_logs,
+            set_log_level,
             test_whisper,
             get_models_dir,
             download_model,
@@ -716,10 +1748,45 @@ fn main() {
             list_vosk_models,
             transcribe_file,
             transcribe_file_advanced,
+            transcribe_range,
+            adjust_subtitles,
+            generate_transcript_document,
+            list_history,
+            delete_history_entry,
+            update_segment,
+            merge_segments,
+            split_segment,
+            regenerate_subtitles,
+            get_audio_waveform,
+            list_glossaries,
+            save_glossary,
+            delete_glossary,
+            list_jobs,
+            get_job_status,
+            set_max_concurrent_jobs,
+            get_max_concurrent_jobs,
+            detect_language,
+            check_model_requirements,
+            benchmark_model,
+            benchmark_all_installed,
+            start_api_server,
+            stop_api_server,
+            get_api_server_status,
+            start_caption_server,
+            stop_caption_server,
+            set_caption_session,
+            get_caption_server_status,
+            set_dictation_settings,
+            get_dictation_status,
             transcribe_audio_chunk,
             start_vosk_session,
             process_vosk_chunk,
             end_vosk_session,
+            get_vosk_session_info,
+            list_vosk_sessions,
+            force_close_all_sessions,
+            set_vosk_idle_timeout,
+            get_vosk_idle_timeout,
         ]);
     }
 
@@ -729,12 +1796,44 @@ fn main() {
         builder = builder.invoke_handler(tauri::generate_handler![
             hello_world,
             get_gpu_info,
+            get_recent_logs,
+            set_log_level,
             test_whisper,
             get_models_dir,
             download_model,
             list_downloaded_models,
             transcribe_file,
             transcribe_file_advanced,
+            transcribe_range,
+            adjust_subtitles,
+            generate_transcript_document,
+            list_history,
+            delete_history_entry,
+            update_segment,
+            merge_segments,
+            split_segment,
+            regenerate_subtitles,
+            get_audio_waveform,
+            list_glossaries,
+            save_glossary,
+            delete_glossary,
+            list_jobs,
+            get_job_status,
+            set_max_concurrent_jobs,
+            get_max_concurrent_jobs,
+            detect_language,
+            check_model_requirements,
+            benchmark_model,
+            benchmark_all_installed,
+            start_api_server,
+            stop_api_server,
+            get_api_server_status,
+            start_caption_server,
+            stop_caption_server,
+            set_caption_session,
+            get_caption_server_status,
+            set_dictation_settings,
+            get_dictation_status,
             transcribe_audio_chunk,
         ]);
     }
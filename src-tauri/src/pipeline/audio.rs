@@ -0,0 +1,203 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::Command;
+
+/// Optional ffmpeg filters applied while converting to 16kHz mono WAV
+/// (all disabled by default)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AudioPreprocessingSettings {
+    /// Remove low-frequency rumble below this frequency (e.g. 80 Hz)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub highpass_hz: Option<u32>,
+    /// FFT-based broadband noise reduction (`afftdn`)
+    #[serde(default)]
+    pub noise_reduction: bool,
+    /// EBU R128 loudness normalization (`loudnorm`), helps quiet recordings
+    #[serde(default)]
+    pub loudness_normalization: bool,
+}
+
+impl AudioPreprocessingSettings {
+    /// ffmpeg filter chain for the enabled options (None = nothing to apply)
+    /// Order matters: cut rumble first, then denoise, then bring the level up
+    pub fn filter_chain(&self) -> Option<String> {
+        let mut filters: Vec<String> = Vec::new();
+
+        if let Some(frequency) = self.highpass_hz.filter(|f| *f > 0) {
+            filters.push(format!("highpass=f={}", frequency));
+        }
+        if self.noise_reduction {
+            filters.push("afftdn".to_string());
+        }
+        if self.loudness_normalization {
+            filters.push("loudnorm=I=-16:TP=-1.5:LRA=11".to_string());
+        }
+
+        if filters.is_empty() {
+            None
+        } else {
+            Some(filters.join(","))
+        }
+    }
+}
+
+/// What the preprocessing stage did, reported in the progress events
+#[derive(Debug, Clone, Serialize)]
+pub struct PreprocessingReport {
+    pub filters: String,            // ffmpeg filter chain that was applied
+    pub rms_before_db: Option<f32>, // mean (RMS) level of the input, in dBFS
+    pub rms_after_db: Option<f32>,  // mean (RMS) level after the filters, in dBFS
+}
+
+/// Parse the `mean_volume` lines printed by ffmpeg's `volumedetect` filters,
+/// ordered by filter position in the chain
+fn parse_mean_volumes(stderr: &str) -> Vec<f32> {
+    // Lines look like: "[Parsed_volumedetect_0 @ 0x...] mean_volume: -27.4 dB"
+    let mut volumes: Vec<(usize, f32)> = stderr
+        .lines()
+        .filter_map(|line| {
+            let filter_index = line
+                .split("Parsed_volumedetect_")
+                .nth(1)?
+                .split(|c: char| !c.is_ascii_digit())
+                .next()?
+                .parse::<usize>()
+                .ok()?;
+            let volume = line
+                .split("mean_volume:")
+                .nth(1)?
+                .trim()
+                .trim_end_matches("dB")
+                .trim()
+                .parse::<f32>()
+                .ok()?;
+            Some((filter_index, volume))
+        })
+        .collect();
+
+    volumes.sort_by_key(|(filter_index, _)| *filter_index);
+    volumes.into_iter().map(|(_, volume)| volume).collect()
+}
+
+/// Run ffmpeg with `input_args`, writing a 16kHz mono 16-bit WAV to `output_str`.
+///
+/// When preprocessing is enabled, the filters are wrapped between two
+/// `volumedetect` filters so the before/after levels are measured in the same pass.
+fn run_ffmpeg_to_wav(
+    input_args: Vec<String>,
+    output_str: &str,
+    preprocessing: Option<&AudioPreprocessingSettings>,
+    error_label: &str,
+) -> Result<Option<PreprocessingReport>> {
+    let filters = preprocessing.and_then(|p| p.filter_chain());
+
+    let mut args = input_args;
+    if let Some(filters) = &filters {
+        args.push("-af".into());
+        args.push(format!("volumedetect,{},volumedetect", filters));
+    }
+
+    args.extend(
+        [
+            "-ar",
+            "16000",
+            "-ac",
+            "1",
+            "-c:a",
+            "pcm_s16le",
+            "-y",
+            output_str,
+        ]
+        .iter()
+        .map(|arg| arg.to_string()),
+    );
+
+    // TODO: Use ffmpeg-sidecar to auto-download/bundle ffmpeg instead of relying on system installation
+    // Currently requires user to have ffmpeg installed on their system
+    let status = Command::new("ffmpeg")
+        .args(&args)
+        .output()
+        .context("Failed to run ffmpeg")?;
+
+    if !status.status.success() {
+        anyhow::bail!(
+            "ffmpeg {} failed: {}",
+            error_label,
+            String::from_utf8_lossy(&status.stderr)
+        );
+    }
+
+    Ok(filters.map(|filters| {
+        let volumes = parse_mean_volumes(&String::from_utf8_lossy(&status.stderr));
+        PreprocessingReport {
+            filters,
+            rms_before_db: volumes.first().copied(),
+            rms_after_db: volumes.get(1).copied(),
+        }
+    }))
+}
+
+/// Convert audio to 16kHz mono WAV (applying `preprocessing` filters) and get duration
+pub fn convert_audio_with_ffmpeg(
+    input_path: &Path,
+    output_path: &Path,
+    preprocessing: Option<&AudioPreprocessingSettings>,
+) -> Result<(f64, Option<PreprocessingReport>)> {
+    let input_str = input_path.to_str().context("Invalid input path encoding")?;
+    let output_str = output_path
+        .to_str()
+        .context("Invalid output path encoding")?;
+
+    let duration_output = Command::new("ffprobe")
+        .args([
+            "-v",
+            "error",
+            "-show_entries",
+            "format=duration",
+            "-of",
+            "default=noprint_wrappers=1:nokey=1",
+            input_str,
+        ])
+        .output()
+        .context("Failed to run ffprobe")?;
+
+    let duration: f64 = String::from_utf8_lossy(&duration_output.stdout)
+        .trim()
+        .parse()
+        .unwrap_or(0.0);
+
+    let report = run_ffmpeg_to_wav(
+        vec!["-i".into(), input_str.into()],
+        output_str,
+        preprocessing,
+        "conversion",
+    )?;
+
+    Ok((duration, report))
+}
+
+/// Extract a time window of the input as 16kHz mono WAV (applying `preprocessing` filters)
+/// `duration_sec` of None means "until the end of the file"
+pub fn extract_audio_window_with_ffmpeg(
+    input_path: &Path,
+    output_path: &Path,
+    start_sec: f64,
+    duration_sec: Option<f64>,
+    preprocessing: Option<&AudioPreprocessingSettings>,
+) -> Result<Option<PreprocessingReport>> {
+    let input_str = input_path.to_str().context("Invalid input path encoding")?;
+    let output_str = output_path
+        .to_str()
+        .context("Invalid output path encoding")?;
+
+    let start_arg = format!("{:.3}", start_sec.max(0.0));
+    let mut args: Vec<String> = vec!["-ss".into(), start_arg, "-i".into(), input_str.into()];
+
+    if let Some(duration) = duration_sec {
+        args.push("-t".into());
+        args.push(format!("{:.3}", duration));
+    }
+
+    run_ffmpeg_to_wav(args, output_str, preprocessing, "extraction")
+}
@@ -0,0 +1,495 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::pipeline::subtitles::SubtitleSegment;
+
+/// A transcript segment to export (same shape as `SubtitleSegment`, plus an
+/// optional speaker label, so subtitle segments can be sent as-is)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptSegment {
+    pub start_time: f64,
+    pub end_time: f64,
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speaker: Option<String>, // e.g. "Alice" or "Speaker 1"
+}
+
+impl From<&SubtitleSegment> for TranscriptSegment {
+    fn from(segment: &SubtitleSegment) -> Self {
+        Self {
+            start_time: segment.start_time,
+            end_time: segment.end_time,
+            text: segment.text.clone(),
+            speaker: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DocumentFormat {
+    #[default]
+    Markdown,
+    Html,
+}
+
+/// How segments are grouped into paragraphs and chapters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TranscriptDocumentOptions {
+    pub format: DocumentFormat,
+    /// Document heading (e.g. "Weekly sync - 2024-03-12")
+    pub title: Option<String>,
+    /// A pause at least this long (seconds) starts a new paragraph
+    pub paragraph_pause_seconds: f64,
+    /// A silence at least this long (seconds) starts a new chapter (None = disabled)
+    pub chapter_silence_seconds: Option<f64>,
+    /// Phrases that open a new chapter when a segment contains them
+    /// (e.g. "next topic", "moving on"), matched case-insensitively
+    pub chapter_keywords: Vec<String>,
+    /// Prefix every paragraph with its start time
+    pub include_timestamps: bool,
+}
+
+impl Default for TranscriptDocumentOptions {
+    fn default() -> Self {
+        Self {
+            format: DocumentFormat::Markdown,
+            title: None,
+            paragraph_pause_seconds: 2.0,
+            chapter_silence_seconds: Some(10.0),
+            chapter_keywords: Vec::new(),
+            include_timestamps: true,
+        }
+    }
+}
+
+/// A chapter boundary, returned so the UI can show a table of contents
+#[derive(Debug, Clone, Serialize)]
+pub struct TranscriptChapter {
+    pub title: String,
+    pub start_time: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TranscriptDocument {
+    pub format: DocumentFormat,
+    pub content: String,
+    pub chapters: Vec<TranscriptChapter>, // empty when no boundary was found
+    pub paragraph_count: usize,
+}
+
+/// Consecutive segments of the same speaker without a long pause
+struct Paragraph {
+    start_time: f64,
+    speaker: Option<String>,
+    text: String,
+}
+
+struct Chapter {
+    title: String,
+    start_time: f64,
+    paragraphs: Vec<Paragraph>,
+}
+
+/// Chapter headings taken from a keyword segment are cut to this many words
+const MAX_CHAPTER_TITLE_WORDS: usize = 8;
+
+/// Format a timestamp for documents (HH:MM:SS)
+fn format_timestamp_clock(seconds: f64) -> String {
+    let total = seconds.max(0.0).floor() as u64;
+    format!(
+        "{:02}:{:02}:{:02}",
+        total / 3600,
+        (total % 3600) / 60,
+        total % 60
+    )
+}
+
+fn validate_options(options: &TranscriptDocumentOptions) -> Result<()> {
+    if !options.paragraph_pause_seconds.is_finite() || options.paragraph_pause_seconds <= 0.0 {
+        anyhow::bail!("Paragraph pause must be greater than 0 seconds");
+    }
+
+    if let Some(silence) = options.chapter_silence_seconds {
+        if silence < options.paragraph_pause_seconds {
+            anyhow::bail!(
+                "Chapter silence ({}s) can't be shorter than the paragraph pause ({}s)",
+                silence,
+                options.paragraph_pause_seconds
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Heading for a chapter opened by a keyword: the start of the segment that said it
+fn keyword_chapter_title(text: &str) -> String {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let title = words
+        .iter()
+        .take(MAX_CHAPTER_TITLE_WORDS)
+        .copied()
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let title = title.trim_end_matches(|c: char| c.is_ascii_punctuation());
+    if words.len() > MAX_CHAPTER_TITLE_WORDS {
+        format!("{}...", title)
+    } else {
+        title.to_string()
+    }
+}
+
+/// Group sorted segments into chapters of paragraphs
+fn group_segments(
+    segments: &[TranscriptSegment],
+    options: &TranscriptDocumentOptions,
+) -> Vec<Chapter> {
+    let keywords: Vec<String> = options
+        .chapter_keywords
+        .iter()
+        .map(|keyword| keyword.trim().to_lowercase())
+        .filter(|keyword| !keyword.is_empty())
+        .collect();
+
+    let mut chapters: Vec<Chapter> = Vec::new();
+    let mut part_count = 0; // "Part N" chapters, keyword chapters have their own titles
+    let mut previous_end: Option<f64> = None;
+
+    for segment in segments {
+        let text = segment.text.trim();
+        if text.is_empty() {
+            continue;
+        }
+
+        let pause = previous_end.map(|end| segment.start_time - end);
+        let lowercase_text = text.to_lowercase();
+
+        let is_long_silence = matches!(
+            (pause, options.chapter_silence_seconds),
+            (Some(pause), Some(silence)) if pause >= silence
+        );
+        let has_keyword = keywords
+            .iter()
+            .any(|keyword| lowercase_text.contains(keyword.as_str()));
+
+        // The first segment always opens the first chapter
+        if chapters.is_empty() || is_long_silence || has_keyword {
+            let title = if has_keyword {
+                keyword_chapter_title(text)
+            } else {
+                part_count += 1;
+                format!("Part {}", part_count)
+            };
+            chapters.push(Chapter {
+                title,
+                start_time: segment.start_time,
+                paragraphs: Vec::new(),
+            });
+        }
+
+        let Some(chapter) = chapters.last_mut() else {
+            continue;
+        };
+
+        let continues_paragraph = chapter.paragraphs.last().is_some_and(|paragraph| {
+            paragraph.speaker == segment.speaker
+                && pause.is_some_and(|pause| pause < options.paragraph_pause_seconds)
+        });
+
+        match chapter.paragraphs.last_mut() {
+            Some(paragraph) if continues_paragraph => {
+                paragraph.text.push(' ');
+                paragraph.text.push_str(text);
+            }
+            _ => chapter.paragraphs.push(Paragraph {
+                start_time: segment.start_time,
+                speaker: segment.speaker.clone(),
+                text: text.to_string(),
+            }),
+        }
+
+        previous_end = Some(segment.end_time);
+    }
+
+    chapters
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn render_markdown(
+    chapters: &[Chapter],
+    options: &TranscriptDocumentOptions,
+    with_headings: bool,
+) -> String {
+    let mut markdown = String::new();
+
+    if let Some(title) = &options.title {
+        markdown.push_str(&format!("# {}\n\n", title.trim()));
+    }
+
+    for chapter in chapters {
+        if with_headings {
+            markdown.push_str(&format!(
+                "## {} ({})\n\n",
+                chapter.title,
+                format_timestamp_clock(chapter.start_time)
+            ));
+        }
+
+        for paragraph in &chapter.paragraphs {
+            let mut prefix = Vec::new();
+            if options.include_timestamps {
+                prefix.push(format!(
+                    "[{}]",
+                    format_timestamp_clock(paragraph.start_time)
+                ));
+            }
+            if let Some(speaker) = &paragraph.speaker {
+                prefix.push(format!("{}:", speaker));
+            }
+
+            if prefix.is_empty() {
+                markdown.push_str(&format!("{}\n\n", paragraph.text));
+            } else {
+                markdown.push_str(&format!("**{}** {}\n\n", prefix.join(" "), paragraph.text));
+            }
+        }
+    }
+
+    format!("{}\n", markdown.trim_end())
+}
+
+fn render_html(
+    chapters: &[Chapter],
+    options: &TranscriptDocumentOptions,
+    with_headings: bool,
+) -> String {
+    let mut html = String::from("<article class=\"transcript\">\n");
+
+    if let Some(title) = &options.title {
+        html.push_str(&format!("  <h1>{}</h1>\n", escape_html(title.trim())));
+    }
+
+    for chapter in chapters {
+        if with_headings {
+            html.push_str(&format!(
+                "  <h2>{} <small>{}</small></h2>\n",
+                escape_html(&chapter.title),
+                format_timestamp_clock(chapter.start_time)
+            ));
+        }
+
+        for paragraph in &chapter.paragraphs {
+            html.push_str("  <p>");
+            if options.include_timestamps {
+                let timestamp = format_timestamp_clock(paragraph.start_time);
+                html.push_str(&format!(
+                    "<time datetime=\"PT{:.0}S\">[{}]</time> ",
+                    paragraph.start_time.max(0.0).floor(),
+                    timestamp
+                ));
+            }
+            if let Some(speaker) = &paragraph.speaker {
+                html.push_str(&format!("<strong>{}:</strong> ", escape_html(speaker)));
+            }
+            html.push_str(&escape_html(&paragraph.text));
+            html.push_str("</p>\n");
+        }
+    }
+
+    html.push_str("</article>\n");
+    html
+}
+
+/// Build a readable document (meeting notes) from transcript segments.
+///
+/// Segments are merged into paragraphs until the speaker changes or a pause
+/// of `paragraph_pause_seconds` occurs. Long silences and keywords open new
+/// chapters; chapter headings are only written when there are several chapters.
+pub fn generate_transcript_document(
+    segments: &[TranscriptSegment],
+    options: &TranscriptDocumentOptions,
+) -> Result<TranscriptDocument> {
+    validate_options(options)?;
+
+    let mut segments = segments.to_vec();
+    segments.sort_by(|a, b| a.start_time.total_cmp(&b.start_time));
+
+    let chapters = group_segments(&segments, options);
+    let with_headings = chapters.len() > 1;
+
+    let content = match options.format {
+        DocumentFormat::Markdown => render_markdown(&chapters, options, with_headings),
+        DocumentFormat::Html => render_html(&chapters, options, with_headings),
+    };
+
+    Ok(TranscriptDocument {
+        format: options.format,
+        content,
+        chapters: if with_headings {
+            chapters
+                .iter()
+                .map(|chapter| TranscriptChapter {
+                    title: chapter.title.clone(),
+                    start_time: chapter.start_time,
+                })
+                .collect()
+        } else {
+            Vec::new()
+        },
+        paragraph_count: chapters
+            .iter()
+            .map(|chapter| chapter.paragraphs.len())
+            .sum(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(
+        start_time: f64,
+        end_time: f64,
+        text: &str,
+        speaker: Option<&str>,
+    ) -> TranscriptSegment {
+        TranscriptSegment {
+            start_time,
+            end_time,
+            text: text.to_string(),
+            speaker: speaker.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_paragraphs_split_on_pause_and_speaker_change() {
+        let segments = [
+            segment(0.0, 1.0, "Hello there.", Some("Alice")),
+            segment(1.5, 2.5, "How are you?", Some("Alice")),
+            segment(3.0, 4.0, "Fine.", Some("Bob")),
+            segment(7.0, 8.0, "Anyway.", Some("Bob")),
+        ];
+
+        let document =
+            generate_transcript_document(&segments, &TranscriptDocumentOptions::default()).unwrap();
+
+        assert_eq!(document.paragraph_count, 3);
+        assert!(document.chapters.is_empty());
+        assert_eq!(
+            document.content,
+            "**[00:00:00] Alice:** Hello there. How are you?\n\n\
+             **[00:00:03] Bob:** Fine.\n\n\
+             **[00:00:07] Bob:** Anyway.\n"
+        );
+    }
+
+    #[test]
+    fn test_chapters_split_on_silence_and_keyword() {
+        let options = TranscriptDocumentOptions {
+            chapter_keywords: vec!["Next topic".to_string()],
+            include_timestamps: false,
+            ..TranscriptDocumentOptions::default()
+        };
+        let segments = [
+            segment(0.0, 1.0, "Intro.", None),
+            segment(2.0, 3.0, "Still intro.", None),
+            segment(20.0, 21.0, "After a break.", None),
+            segment(22.0, 23.0, "Next topic: the budget.", None),
+            segment(40.0, 41.0, "Back again.", None),
+        ];
+
+        let document = generate_transcript_document(&segments, &options).unwrap();
+
+        let titles: Vec<&str> = document
+            .chapters
+            .iter()
+            .map(|chapter| chapter.title.as_str())
+            .collect();
+        let start_times: Vec<f64> = document
+            .chapters
+            .iter()
+            .map(|chapter| chapter.start_time)
+            .collect();
+
+        // Keyword chapters don't take a part number
+        assert_eq!(
+            titles,
+            ["Part 1", "Part 2", "Next topic: the budget", "Part 3"]
+        );
+        assert_eq!(start_times, [0.0, 20.0, 22.0, 40.0]);
+        assert_eq!(document.paragraph_count, 4);
+        assert!(document
+            .content
+            .contains("## Part 3 (00:00:40)\n\nBack again.\n"));
+    }
+
+    #[test]
+    fn test_keyword_chapter_title_is_truncated() {
+        assert_eq!(
+            keyword_chapter_title(
+                "So the next topic is the quarterly budget review for this year."
+            ),
+            "So the next topic is the quarterly budget..."
+        );
+        assert_eq!(
+            keyword_chapter_title("Moving on, please!"),
+            "Moving on, please"
+        );
+    }
+
+    #[test]
+    fn test_validate_options() {
+        let defaults = TranscriptDocumentOptions::default();
+        assert!(validate_options(&defaults).is_ok());
+
+        let no_chapters = TranscriptDocumentOptions {
+            chapter_silence_seconds: None,
+            ..TranscriptDocumentOptions::default()
+        };
+        assert!(validate_options(&no_chapters).is_ok());
+
+        for paragraph_pause_seconds in [0.0, -1.0, f64::NAN] {
+            let options = TranscriptDocumentOptions {
+                paragraph_pause_seconds,
+                ..TranscriptDocumentOptions::default()
+            };
+            assert!(validate_options(&options).is_err());
+        }
+
+        let short_silence = TranscriptDocumentOptions {
+            chapter_silence_seconds: Some(1.0),
+            ..TranscriptDocumentOptions::default()
+        };
+        assert!(validate_options(&short_silence).is_err());
+    }
+
+    #[test]
+    fn test_html_is_escaped() {
+        let options = TranscriptDocumentOptions {
+            format: DocumentFormat::Html,
+            title: Some("Q&A <draft>".to_string()),
+            ..TranscriptDocumentOptions::default()
+        };
+        let segments = [segment(65.4, 67.0, "1 < 2 & 3 > 2", Some("Tom \"T\""))];
+
+        let document = generate_transcript_document(&segments, &options).unwrap();
+
+        assert_eq!(
+            document.content,
+            "<article class=\"transcript\">\n  \
+             <h1>Q&amp;A &lt;draft&gt;</h1>\n  \
+             <p><time datetime=\"PT65S\">[00:01:05]</time> \
+             <strong>Tom &quot;T&quot;:</strong> 1 &lt; 2 &amp; 3 &gt; 2</p>\n\
+             </article>\n"
+        );
+    }
+}
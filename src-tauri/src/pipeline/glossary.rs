@@ -0,0 +1,360 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::{debug, warn};
+
+use crate::whisper_rs_imp::transcriber::{TranscribedSegment, TranscriptionSettings};
+
+/// Glossaries are stored together in a single JSON file in the app data directory
+const GLOSSARY_FILE_NAME: &str = "glossaries.json";
+
+/// Whisper only keeps the end of long prompts (~224 tokens), so glossary terms
+/// are capped to leave room for the user's own prompt
+const MAX_PROMPT_TERMS_CHARS: usize = 600;
+
+/// A find/replace correction applied to the transcribed text
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GlossaryReplacement {
+    pub find: String,    // e.g. "cube ernie tees"
+    pub replace: String, // e.g. "Kubernetes"
+    /// Match case exactly (default: ASCII case-insensitive)
+    #[serde(default)]
+    pub case_sensitive: bool,
+}
+
+/// A named list of terms (product names, client names, jargon...)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Glossary {
+    pub name: String,
+    /// Terms composed into Whisper's initial prompt so they are spelled correctly
+    #[serde(default)]
+    pub terms: Vec<String>,
+    /// Corrections for words Whisper still gets wrong
+    #[serde(default)]
+    pub replacements: Vec<GlossaryReplacement>,
+}
+
+/// Glossaries selected for a transcription (part of `TranscriptionSettings`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GlossarySettings {
+    /// Names of the glossaries to use
+    #[serde(default)]
+    pub names: Vec<String>,
+    /// Also apply the glossaries' find/replace corrections to the output
+    #[serde(default)]
+    pub apply_replacements: bool,
+}
+
+/// Terms and corrections of the selected glossaries, ready to be applied
+#[derive(Debug, Clone, Default)]
+pub struct ResolvedGlossary {
+    pub terms: Vec<String>,
+    pub replacements: Vec<GlossaryReplacement>,
+}
+
+fn glossary_file(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join(GLOSSARY_FILE_NAME)
+}
+
+/// Load every saved glossary (empty list if none were saved yet)
+pub fn load_glossaries(app_data_dir: &Path) -> Result<Vec<Glossary>> {
+    let path = glossary_file(app_data_dir);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let json = fs::read_to_string(&path).context("Failed to read glossaries")?;
+    serde_json::from_str(&json).context("Failed to parse glossaries")
+}
+
+fn write_glossaries(app_data_dir: &Path, glossaries: &[Glossary]) -> Result<()> {
+    fs::create_dir_all(app_data_dir).context("Failed to create app data directory")?;
+
+    let json =
+        serde_json::to_string_pretty(glossaries).context("Failed to serialize glossaries")?;
+    fs::write(glossary_file(app_data_dir), json).context("Failed to save glossaries")
+}
+
+/// Create or update (by name) a glossary, returns the cleaned-up glossary
+pub fn save_glossary(app_data_dir: &Path, glossary: Glossary) -> Result<Glossary> {
+    let name = glossary.name.trim().to_string();
+    if name.is_empty() {
+        anyhow::bail!("Glossary name cannot be empty");
+    }
+
+    // Trim and de-duplicate terms, keeping the user's order
+    let mut terms: Vec<String> = Vec::new();
+    for term in glossary.terms {
+        let term = term.trim().to_string();
+        if !term.is_empty() && !terms.contains(&term) {
+            terms.push(term);
+        }
+    }
+
+    if let Some(empty) = glossary
+        .replacements
+        .iter()
+        .find(|r| r.find.trim().is_empty())
+    {
+        anyhow::bail!("Replacement for '{}' has nothing to find", empty.replace);
+    }
+
+    let glossary = Glossary {
+        name,
+        terms,
+        replacements: glossary.replacements,
+    };
+
+    let mut glossaries = load_glossaries(app_data_dir)?;
+    match glossaries.iter_mut().find(|g| g.name == glossary.name) {
+        Some(existing) => *existing = glossary.clone(),
+        None => glossaries.push(glossary.clone()),
+    }
+    write_glossaries(app_data_dir, &glossaries)?;
+
+    debug!(
+        "Saved glossary '{}' ({} terms)",
+        glossary.name,
+        glossary.terms.len()
+    );
+    Ok(glossary)
+}
+
+/// Delete a glossary by name (returns false if it didn't exist)
+pub fn delete_glossary(app_data_dir: &Path, name: &str) -> Result<bool> {
+    let mut glossaries = load_glossaries(app_data_dir)?;
+    let count = glossaries.len();
+    glossaries.retain(|g| g.name != name);
+
+    if glossaries.len() == count {
+        return Ok(false);
+    }
+
+    write_glossaries(app_data_dir, &glossaries)?;
+    Ok(true)
+}
+
+/// Load the glossaries selected in `settings` (unknown names are an error)
+pub fn resolve_glossary(
+    app_data_dir: &Path,
+    settings: Option<&TranscriptionSettings>,
+) -> Result<ResolvedGlossary> {
+    let Some(selection) = settings.and_then(|s| s.glossary.as_ref()) else {
+        return Ok(ResolvedGlossary::default());
+    };
+    if selection.names.is_empty() {
+        return Ok(ResolvedGlossary::default());
+    }
+
+    let glossaries = load_glossaries(app_data_dir)?;
+    let mut resolved = ResolvedGlossary::default();
+
+    for name in &selection.names {
+        let glossary = glossaries
+            .iter()
+            .find(|g| &g.name == name)
+            .ok_or_else(|| anyhow::anyhow!("Glossary '{}' not found", name))?;
+
+        for term in &glossary.terms {
+            if !resolved.terms.contains(term) {
+                resolved.terms.push(term.clone());
+            }
+        }
+        if selection.apply_replacements {
+            resolved
+                .replacements
+                .extend(glossary.replacements.iter().cloned());
+        }
+    }
+
+    Ok(resolved)
+}
+
+/// True if `text[start..end]` isn't glued to other letters/digits
+fn is_whole_word(text: &str, start: usize, end: usize) -> bool {
+    let before = text[..start].chars().next_back();
+    let after = text[end..].chars().next();
+
+    !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+}
+
+/// Replace whole-word occurrences of `replacement.find` in `text`
+fn apply_replacement(text: &str, replacement: &GlossaryReplacement) -> String {
+    let find = replacement.find.trim();
+    if find.is_empty() {
+        return text.to_string();
+    }
+
+    let mut output = String::with_capacity(text.len());
+    let mut index = 0;
+
+    while index < text.len() {
+        let end = index + find.len();
+        if end <= text.len() && text.is_char_boundary(end) {
+            let candidate = &text[index..end];
+            let matches = if replacement.case_sensitive {
+                candidate == find
+            } else {
+                candidate.eq_ignore_ascii_case(find)
+            };
+
+            if matches && is_whole_word(text, index, end) {
+                output.push_str(&replacement.replace);
+                index = end;
+                continue;
+            }
+        }
+
+        // `index` always sits on a char boundary (we advance char by char)
+        let Some(ch) = text[index..].chars().next() else {
+            break;
+        };
+        output.push(ch);
+        index += ch.len_utf8();
+    }
+
+    output
+}
+
+impl ResolvedGlossary {
+    /// Append the glossary terms to the user's initial prompt.
+    ///
+    /// Whisper treats the prompt as "previous text", so a plain list of
+    /// correctly spelled terms is enough to bias it towards them.
+    pub fn apply_to_settings(
+        &self,
+        settings: Option<TranscriptionSettings>,
+    ) -> Option<TranscriptionSettings> {
+        if self.terms.is_empty() {
+            return settings;
+        }
+
+        let mut terms_text = String::new();
+        for term in &self.terms {
+            if terms_text.len() + term.len() + 2 > MAX_PROMPT_TERMS_CHARS {
+                warn!(
+                    "Glossary too long for Whisper's prompt, some terms were left out (max {} chars)",
+                    MAX_PROMPT_TERMS_CHARS
+                );
+                break;
+            }
+            if !terms_text.is_empty() {
+                terms_text.push_str(", ");
+            }
+            terms_text.push_str(term);
+        }
+
+        let mut settings = settings.unwrap_or_default();
+        let user_prompt = settings
+            .initial_prompt
+            .as_deref()
+            .map(str::trim)
+            .filter(|p| !p.is_empty());
+
+        settings.initial_prompt = Some(match user_prompt {
+            Some(prompt) => format!("{} {}.", prompt, terms_text),
+            None => format!("{}.", terms_text),
+        });
+
+        debug!("Composed initial prompt: {:?}", settings.initial_prompt);
+        Some(settings)
+    }
+
+    /// Apply the find/replace corrections to every segment
+    pub fn correct_segments(&self, segments: &mut [TranscribedSegment]) {
+        if self.replacements.is_empty() {
+            return;
+        }
+
+        for segment in segments.iter_mut() {
+            let mut text = segment.text.clone();
+            for replacement in &self.replacements {
+                text = apply_replacement(&text, replacement);
+            }
+            segment.text = text;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replacement(find: &str, replace: &str, case_sensitive: bool) -> GlossaryReplacement {
+        GlossaryReplacement {
+            find: find.to_string(),
+            replace: replace.to_string(),
+            case_sensitive,
+        }
+    }
+
+    #[test]
+    fn test_replacement_matches_whole_words_only() {
+        let fix = replacement("cube ernie tees", "Kubernetes", false);
+
+        assert_eq!(
+            apply_replacement("We run Cube Ernie Tees in prod.", &fix),
+            "We run Kubernetes in prod."
+        );
+        assert_eq!(
+            apply_replacement("cube ernie teesting", &fix),
+            "cube ernie teesting"
+        );
+    }
+
+    #[test]
+    fn test_replacement_case_sensitivity() {
+        let fix = replacement("Rust", "Rust 🦀", true);
+
+        assert_eq!(
+            apply_replacement("Rust, not rust or Rusty.", &fix),
+            "Rust 🦀, not rust or Rusty."
+        );
+        assert_eq!(apply_replacement("été Rust", &fix), "été Rust 🦀");
+    }
+
+    #[test]
+    fn test_correct_segments() {
+        let glossary = ResolvedGlossary {
+            terms: Vec::new(),
+            replacements: vec![replacement("tauri", "Tauri", false)],
+        };
+        let mut segments = vec![TranscribedSegment {
+            start: 0.0,
+            end: 1.0,
+            text: "Built with tauri and whisper".to_string(),
+            avg_probability: 0.9,
+            no_speech_probability: 0.1,
+            temperature: 0.0,
+        }];
+
+        glossary.correct_segments(&mut segments);
+
+        assert_eq!(segments[0].text, "Built with Tauri and whisper");
+    }
+
+    #[test]
+    fn test_terms_are_appended_to_the_prompt() {
+        let glossary = ResolvedGlossary {
+            terms: vec!["Kubernetes".to_string(), "Tauri".to_string()],
+            replacements: Vec::new(),
+        };
+        let settings = TranscriptionSettings {
+            initial_prompt: Some(" Meeting notes. ".to_string()),
+            ..Default::default()
+        };
+
+        let composed = glossary.apply_to_settings(Some(settings)).unwrap();
+        assert_eq!(
+            composed.initial_prompt.as_deref(),
+            Some("Meeting notes. Kubernetes, Tauri.")
+        );
+
+        let composed = glossary.apply_to_settings(None).unwrap();
+        assert_eq!(
+            composed.initial_prompt.as_deref(),
+            Some("Kubernetes, Tauri.")
+        );
+    }
+}
@@ -0,0 +1,598 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{debug, warn};
+
+use super::subtitles::{generate_ass, generate_srt, generate_vtt, SubtitleSegment};
+use super::TranscriptionResult;
+
+/// Transcription results are stored one JSON file per result in this folder
+/// of the app data directory
+const HISTORY_DIR_NAME: &str = "history";
+
+/// Oldest results are deleted once the history holds more than this
+const MAX_HISTORY_ENTRIES: usize = 200;
+
+/// Timestamps closer than this are considered equal (float rounding)
+const TIME_EPSILON: f64 = 0.001;
+
+/// A saved transcription result, edited in place by the segment commands.
+/// Segments are the source of truth, subtitle files are regenerated from them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub result_id: String, // id of the job that produced the result
+    pub file_path: String,
+    pub model_name: String,
+    pub language: String,
+    pub segments: Vec<SubtitleSegment>,
+    pub created_at_ms: u64,
+    pub updated_at_ms: u64,
+}
+
+/// A saved result without its segments, for listing the history
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryEntrySummary {
+    pub result_id: String,
+    pub file_path: String,
+    pub model_name: String,
+    pub language: String,
+    pub segment_count: usize,
+    pub created_at_ms: u64,
+    pub updated_at_ms: u64,
+}
+
+impl From<HistoryEntry> for HistoryEntrySummary {
+    fn from(entry: HistoryEntry) -> Self {
+        Self {
+            result_id: entry.result_id,
+            file_path: entry.file_path,
+            model_name: entry.model_name,
+            language: entry.language,
+            segment_count: entry.segments.len(),
+            created_at_ms: entry.created_at_ms,
+            updated_at_ms: entry.updated_at_ms,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Srt,
+    Vtt,
+    Ass,
+    Txt,
+}
+
+/// Subtitle files regenerated from the edited segments (only the requested formats)
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RegeneratedSubtitles {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subtitles_srt: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subtitles_vtt: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subtitles_ass: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Result ids end up in file names, keep them simple (same rules as job ids)
+fn entry_path(app_data_dir: &Path, result_id: &str) -> Result<PathBuf> {
+    let is_valid = !result_id.is_empty()
+        && result_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !is_valid {
+        anyhow::bail!("Invalid result id '{}'", result_id);
+    }
+
+    Ok(app_data_dir
+        .join(HISTORY_DIR_NAME)
+        .join(format!("{}.json", result_id)))
+}
+
+fn write_entry(app_data_dir: &Path, entry: &HistoryEntry) -> Result<()> {
+    let path = entry_path(app_data_dir, &entry.result_id)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).context("Failed to create history directory")?;
+    }
+
+    let json = serde_json::to_string_pretty(entry).context("Failed to serialize result")?;
+    fs::write(&path, json).context("Failed to save result to history")
+}
+
+/// Store a finished transcription so its segments can be edited later.
+/// The oldest results are deleted past `MAX_HISTORY_ENTRIES`.
+pub fn save_result(
+    app_data_dir: &Path,
+    result_id: &str,
+    file_path: &str,
+    model_name: &str,
+    result: &TranscriptionResult,
+) -> Result<()> {
+    let now = now_ms();
+    let entry = HistoryEntry {
+        result_id: result_id.to_string(),
+        file_path: file_path.to_string(),
+        model_name: model_name.to_string(),
+        language: result.language.clone(),
+        segments: result.segments.clone(),
+        created_at_ms: now,
+        updated_at_ms: now,
+    };
+
+    write_entry(app_data_dir, &entry)?;
+    debug!("Saved result {} to history", result_id);
+
+    prune_history(app_data_dir, MAX_HISTORY_ENTRIES)
+}
+
+/// Every saved result, most recent first (unreadable files are skipped)
+pub fn list_entries(app_data_dir: &Path) -> Result<Vec<HistoryEntrySummary>> {
+    let history_dir = app_data_dir.join(HISTORY_DIR_NAME);
+    if !history_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut entries = Vec::new();
+    for dir_entry in fs::read_dir(&history_dir)
+        .context("Failed to read history directory")?
+        .flatten()
+    {
+        let path = dir_entry.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+            continue;
+        }
+        let Some(result_id) = path.file_stem().and_then(|stem| stem.to_str()) else {
+            continue;
+        };
+
+        match load_entry(app_data_dir, result_id) {
+            Ok(entry) => entries.push(HistoryEntrySummary::from(entry)),
+            Err(e) => warn!("Skipping history file {}: {:#}", path.display(), e),
+        }
+    }
+
+    entries.sort_by_key(|entry| std::cmp::Reverse(entry.created_at_ms));
+    Ok(entries)
+}
+
+pub fn delete_entry(app_data_dir: &Path, result_id: &str) -> Result<()> {
+    let path = entry_path(app_data_dir, result_id)?;
+    if !path.exists() {
+        anyhow::bail!("Result '{}' not found in history", result_id);
+    }
+
+    fs::remove_file(&path).context("Failed to delete result from history")
+}
+
+/// Delete the oldest results so at most `max_entries` are kept
+fn prune_history(app_data_dir: &Path, max_entries: usize) -> Result<()> {
+    let entries = list_entries(app_data_dir)?;
+
+    for entry in entries.iter().skip(max_entries) {
+        debug!("Removing old result {} from history", entry.result_id);
+        delete_entry(app_data_dir, &entry.result_id)?;
+    }
+
+    Ok(())
+}
+
+pub fn load_entry(app_data_dir: &Path, result_id: &str) -> Result<HistoryEntry> {
+    let path = entry_path(app_data_dir, result_id)?;
+    if !path.exists() {
+        anyhow::bail!("Result '{}' not found in history", result_id);
+    }
+
+    let json = fs::read_to_string(&path).context("Failed to read result from history")?;
+    serde_json::from_str(&json).context("Failed to parse result from history")
+}
+
+/// Load an entry, apply `edit` to its segments and save it back.
+/// Returns the edited segments.
+fn edit_segments(
+    app_data_dir: &Path,
+    result_id: &str,
+    edit: impl FnOnce(&mut Vec<SubtitleSegment>) -> Result<()>,
+) -> Result<Vec<SubtitleSegment>> {
+    let mut entry = load_entry(app_data_dir, result_id)?;
+
+    edit(&mut entry.segments)?;
+    for (index, segment) in entry.segments.iter_mut().enumerate() {
+        segment.index = index;
+    }
+
+    entry.updated_at_ms = now_ms();
+    write_entry(app_data_dir, &entry)?;
+
+    Ok(entry.segments)
+}
+
+fn check_index(segments: &[SubtitleSegment], index: usize) -> Result<()> {
+    if index >= segments.len() {
+        anyhow::bail!(
+            "Segment {} doesn't exist ({} segments)",
+            index,
+            segments.len()
+        );
+    }
+    Ok(())
+}
+
+/// Check a segment's timing against its neighbours (non-overlapping, monotonic)
+fn check_timing(segments: &[SubtitleSegment], index: usize) -> Result<()> {
+    let segment = &segments[index];
+
+    if !segment.start_time.is_finite() || !segment.end_time.is_finite() {
+        anyhow::bail!("Segment {} has an invalid timestamp", index);
+    }
+    if segment.start_time < 0.0 {
+        anyhow::bail!("Segment {} can't start before 0s", index);
+    }
+    if segment.end_time <= segment.start_time {
+        anyhow::bail!(
+            "Segment {} must end after it starts ({:.3}s - {:.3}s)",
+            index,
+            segment.start_time,
+            segment.end_time
+        );
+    }
+
+    if let Some(previous) = index.checked_sub(1).map(|i| &segments[i]) {
+        if segment.start_time < previous.end_time - TIME_EPSILON {
+            anyhow::bail!(
+                "Segment {} would overlap the previous segment (ends at {:.3}s)",
+                index,
+                previous.end_time
+            );
+        }
+    }
+    if let Some(next) = segments.get(index + 1) {
+        if segment.end_time > next.start_time + TIME_EPSILON {
+            anyhow::bail!(
+                "Segment {} would overlap the next segment (starts at {:.3}s)",
+                index,
+                next.start_time
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Change the text and/or timing of one segment
+pub fn update_segment(
+    app_data_dir: &Path,
+    result_id: &str,
+    index: usize,
+    new_text: Option<String>,
+    new_start: Option<f64>,
+    new_end: Option<f64>,
+) -> Result<Vec<SubtitleSegment>> {
+    edit_segments(app_data_dir, result_id, |segments| {
+        check_index(segments, index)?;
+
+        let timing_changed = new_start.is_some() || new_end.is_some();
+        let segment = &mut segments[index];
+        if let Some(text) = new_text {
+            let text = text.trim().to_string();
+            if text.is_empty() {
+                anyhow::bail!("Segment text can't be empty (merge it with a neighbour instead)");
+            }
+            segment.text = text;
+        }
+        if let Some(start) = new_start {
+            segment.start_time = start;
+        }
+        if let Some(end) = new_end {
+            segment.end_time = end;
+        }
+
+        // Text-only edits keep working on results whose timing was already off
+        if timing_changed {
+            check_timing(segments, index)?;
+        }
+        Ok(())
+    })
+}
+
+/// Merge segments `first_index..=last_index` into one
+pub fn merge_segments(
+    app_data_dir: &Path,
+    result_id: &str,
+    first_index: usize,
+    last_index: usize,
+) -> Result<Vec<SubtitleSegment>> {
+    edit_segments(app_data_dir, result_id, |segments| {
+        check_index(segments, last_index)?;
+        if first_index >= last_index {
+            anyhow::bail!("At least two segments are needed to merge");
+        }
+
+        let merged: Vec<SubtitleSegment> = segments.drain(first_index..=last_index).collect();
+
+        // Probabilities are averaged over the segments that have one
+        let average = |values: Vec<f32>| {
+            (!values.is_empty()).then(|| values.iter().sum::<f32>() / values.len() as f32)
+        };
+        let avg_probability = average(merged.iter().filter_map(|s| s.avg_probability).collect());
+        let no_speech_probability = average(
+            merged
+                .iter()
+                .filter_map(|s| s.no_speech_probability)
+                .collect(),
+        );
+        // Keep the highest temperature, it flags the least reliable part
+        let temperature = merged.iter().filter_map(|s| s.temperature).reduce(f32::max);
+
+        debug!("Merging {} segments at {}", merged.len(), first_index);
+
+        segments.insert(
+            first_index,
+            SubtitleSegment {
+                index: first_index,
+                start_time: merged[0].start_time,
+                end_time: merged[merged.len() - 1].end_time,
+                text: merged
+                    .iter()
+                    .map(|s| s.text.trim())
+                    .filter(|text| !text.is_empty())
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                avg_probability,
+                no_speech_probability,
+                temperature,
+            },
+        );
+
+        Ok(())
+    })
+}
+
+/// Split a segment in two at `split_time`, the second part's text starting at
+/// character `text_position`
+pub fn split_segment(
+    app_data_dir: &Path,
+    result_id: &str,
+    index: usize,
+    split_time: f64,
+    text_position: usize,
+) -> Result<Vec<SubtitleSegment>> {
+    edit_segments(app_data_dir, result_id, |segments| {
+        check_index(segments, index)?;
+        let segment = segments[index].clone();
+
+        if !split_time.is_finite()
+            || split_time <= segment.start_time + TIME_EPSILON
+            || split_time >= segment.end_time - TIME_EPSILON
+        {
+            anyhow::bail!(
+                "Split time {:.3}s must be inside the segment ({:.3}s - {:.3}s)",
+                split_time,
+                segment.start_time,
+                segment.end_time
+            );
+        }
+
+        let byte_position = segment
+            .text
+            .char_indices()
+            .nth(text_position)
+            .map(|(byte_position, _)| byte_position)
+            .unwrap_or(segment.text.len());
+        let (first_text, second_text) = segment.text.split_at(byte_position);
+        let (first_text, second_text) = (first_text.trim(), second_text.trim());
+
+        if first_text.is_empty() || second_text.is_empty() {
+            anyhow::bail!("Both parts of a split segment need some text");
+        }
+
+        segments[index] = SubtitleSegment {
+            end_time: split_time,
+            text: first_text.to_string(),
+            ..segment.clone()
+        };
+        segments.insert(
+            index + 1,
+            SubtitleSegment {
+                start_time: split_time,
+                text: second_text.to_string(),
+                ..segment
+            },
+        );
+
+        Ok(())
+    })
+}
+
+/// Regenerate subtitle files from the (edited) segments of a result
+pub fn regenerate_subtitles(
+    app_data_dir: &Path,
+    result_id: &str,
+    formats: &[ExportFormat],
+) -> Result<RegeneratedSubtitles> {
+    let entry = load_entry(app_data_dir, result_id)?;
+    let segments = &entry.segments;
+
+    let mut regenerated = RegeneratedSubtitles::default();
+    for format in formats {
+        match format {
+            ExportFormat::Srt => regenerated.subtitles_srt = Some(generate_srt(segments)),
+            ExportFormat::Vtt => regenerated.subtitles_vtt = Some(generate_vtt(segments)),
+            ExportFormat::Ass => regenerated.subtitles_ass = Some(generate_ass(segments)),
+            ExportFormat::Txt => {
+                regenerated.text = Some(
+                    segments
+                        .iter()
+                        .map(|s| s.text.clone())
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                )
+            }
+        }
+    }
+
+    Ok(regenerated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// App data directory in the system temp folder, deleted when dropped
+    struct TestDir(PathBuf);
+
+    impl TestDir {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!(
+                "tauri-whisper-history-{}-{}",
+                name,
+                std::process::id()
+            ));
+            let _ = fs::remove_dir_all(&dir);
+            Self(dir)
+        }
+    }
+
+    impl Drop for TestDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn segment(index: usize, start_time: f64, end_time: f64, text: &str) -> SubtitleSegment {
+        SubtitleSegment {
+            index,
+            start_time,
+            end_time,
+            text: text.to_string(),
+            avg_probability: None,
+            no_speech_probability: None,
+            temperature: None,
+        }
+    }
+
+    fn save_entry(dir: &Path, result_id: &str, created_at_ms: u64, segments: Vec<SubtitleSegment>) {
+        let entry = HistoryEntry {
+            result_id: result_id.to_string(),
+            file_path: "audio.wav".to_string(),
+            model_name: "base".to_string(),
+            language: "en".to_string(),
+            segments,
+            created_at_ms,
+            updated_at_ms: created_at_ms,
+        };
+        write_entry(dir, &entry).unwrap();
+    }
+
+    fn three_segments() -> Vec<SubtitleSegment> {
+        vec![
+            segment(0, 0.0, 1.0, "Hello"),
+            segment(1, 1.0, 2.5, "there"),
+            segment(2, 3.0, 4.0, "General Kenobi"),
+        ]
+    }
+
+    #[test]
+    fn test_check_timing() {
+        let mut segments = three_segments();
+        assert!(check_timing(&segments, 1).is_ok());
+
+        segments[1].start_time = 0.5; // overlaps the previous segment
+        assert!(check_timing(&segments, 1).is_err());
+
+        segments[1].start_time = 1.0;
+        segments[1].end_time = 3.5; // overlaps the next segment
+        assert!(check_timing(&segments, 1).is_err());
+
+        segments[1].end_time = 1.0; // empty
+        assert!(check_timing(&segments, 1).is_err());
+    }
+
+    #[test]
+    fn test_update_text_only_skips_timing_check() {
+        let dir = TestDir::new("update");
+        let mut segments = three_segments();
+        segments[1].start_time = 0.8; // already overlapping when saved
+        save_entry(&dir.0, "result", 0, segments);
+
+        let updated =
+            update_segment(&dir.0, "result", 1, Some(" fixed ".to_string()), None, None).unwrap();
+        assert_eq!(updated[1].text, "fixed");
+
+        assert!(update_segment(&dir.0, "result", 1, None, Some(0.9), None).is_err());
+    }
+
+    #[test]
+    fn test_merge_segments() {
+        let dir = TestDir::new("merge");
+        let mut segments = three_segments();
+        segments[0].avg_probability = Some(0.5);
+        segments[1].avg_probability = Some(0.9);
+        segments[1].temperature = Some(0.4);
+        save_entry(&dir.0, "result", 0, segments);
+
+        let merged = merge_segments(&dir.0, "result", 0, 1).unwrap();
+
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[0].text, "Hello there");
+        assert_eq!(merged[0].start_time, 0.0);
+        assert_eq!(merged[0].end_time, 2.5);
+        assert!((merged[0].avg_probability.unwrap() - 0.7).abs() < 1e-6);
+        assert_eq!(merged[0].temperature, Some(0.4));
+        assert_eq!(merged[1].index, 1);
+
+        assert!(merge_segments(&dir.0, "result", 1, 1).is_err());
+        assert!(merge_segments(&dir.0, "result", 0, 2).is_err());
+    }
+
+    #[test]
+    fn test_split_segment() {
+        let dir = TestDir::new("split");
+        save_entry(&dir.0, "result", 0, three_segments());
+
+        let split = split_segment(&dir.0, "result", 2, 3.4, 8).unwrap();
+
+        assert_eq!(split.len(), 4);
+        assert_eq!(split[2].text, "General");
+        assert_eq!(split[2].end_time, 3.4);
+        assert_eq!(split[3].text, "Kenobi");
+        assert_eq!(split[3].start_time, 3.4);
+        assert_eq!(split[3].index, 3);
+
+        // Outside the segment, or leaving one part without text
+        assert!(split_segment(&dir.0, "result", 0, 1.5, 2).is_err());
+        assert!(split_segment(&dir.0, "result", 0, 0.5, 0).is_err());
+    }
+
+    #[test]
+    fn test_prune_keeps_most_recent_entries() {
+        let dir = TestDir::new("prune");
+        for created_at_ms in 0..4 {
+            let result_id = format!("result-{}", created_at_ms);
+            save_entry(&dir.0, &result_id, created_at_ms, three_segments());
+        }
+
+        prune_history(&dir.0, 2).unwrap();
+
+        let remaining: Vec<String> = list_entries(&dir.0)
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.result_id)
+            .collect();
+        assert_eq!(remaining, ["result-3", "result-2"]);
+
+        delete_entry(&dir.0, "result-3").unwrap();
+        assert!(load_entry(&dir.0, "result-3").is_err());
+    }
+}
@@ -0,0 +1,146 @@
+//! Transcription pipeline shared by the Tauri commands and the headless CLI:
+//! ffmpeg conversion -> Whisper single pass -> subtitle generation.
+
+pub mod audio;
+pub mod export;
+pub mod glossary;
+pub mod history;
+pub mod models;
+pub mod subtitles;
+pub mod waveform;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+use crate::whisper_rs_imp::transcriber::{transcribe_single_pass, TranscriptionSettings};
+use audio::{convert_audio_with_ffmpeg, PreprocessingReport};
+use glossary::ResolvedGlossary;
+use subtitles::{generate_srt, generate_vtt, to_subtitle_segments, SubtitleSegment};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type")]
+pub enum TranscriptionProgress {
+    #[serde(rename = "converting")]
+    Converting { message: String },
+
+    #[serde(rename = "preprocessed")]
+    Preprocessed {
+        #[serde(flatten)]
+        report: PreprocessingReport,
+    },
+
+    #[serde(rename = "detecting_language")]
+    DetectingLanguage,
+
+    #[serde(rename = "language_detected")]
+    LanguageDetected { language: String },
+
+    #[serde(rename = "transcribing")]
+    Transcribing { progress: u8 },
+
+    #[serde(rename = "generating_subtitles")]
+    GeneratingSubtitles,
+
+    #[serde(rename = "complete")]
+    Complete { subtitle_format: String },
+}
+
+#[derive(Debug, Serialize)]
+pub struct TranscriptionResult {
+    pub text: String,
+    pub subtitles_srt: String,
+    pub subtitles_vtt: String,
+    pub language: String,
+    pub segments: Vec<SubtitleSegment>,
+    /// History entry the segments can be edited in (set by the Tauri command)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result_id: Option<String>,
+}
+
+/// Run the full file transcription pipeline (blocking).
+///
+/// Steps:
+/// 1. Convert the input to 16kHz mono WAV in `temp_dir`
+/// 2. Run single-pass Whisper transcription (glossary terms composed into the prompt)
+/// 3. Apply glossary corrections and generate SRT/VTT subtitles
+///
+/// `on_progress` receives the same events the GUI shows
+/// (emitted as "transcription-progress:{job_id}" by the Tauri command).
+pub fn run_transcription(
+    audio_path: &Path,
+    model_path: &Path,
+    temp_dir: &Path,
+    auto_detect_language: bool,
+    settings: Option<TranscriptionSettings>,
+    glossary: &ResolvedGlossary,
+    on_progress: &dyn Fn(TranscriptionProgress),
+) -> Result<TranscriptionResult> {
+    if !audio_path.exists() {
+        anyhow::bail!("File not found: {}", audio_path.display());
+    }
+
+    fs::create_dir_all(temp_dir).context("Failed to create temp directory")?;
+    let temp_wav = temp_dir.join("temp_audio.wav");
+
+    // Step 1: Convert audio to 16kHz mono WAV (+ optional preprocessing filters)
+    let preprocessing = settings.as_ref().and_then(|s| s.preprocessing.clone());
+    on_progress(TranscriptionProgress::Converting {
+        message: if preprocessing.is_some() {
+            "Converting and preprocessing audio...".to_string()
+        } else {
+            "Converting audio to WAV format...".to_string()
+        },
+    });
+
+    let (_duration, preprocessing_report) =
+        convert_audio_with_ffmpeg(audio_path, &temp_wav, preprocessing.as_ref())?;
+
+    if let Some(report) = preprocessing_report {
+        on_progress(TranscriptionProgress::Preprocessed { report });
+    }
+
+    // Step 2: Run single-pass transcription
+    on_progress(TranscriptionProgress::Transcribing { progress: 50 });
+
+    let settings = glossary.apply_to_settings(settings);
+    let transcription =
+        transcribe_single_pass(model_path, &temp_wav, auto_detect_language, settings);
+
+    // Cleanup before bailing out so failed runs don't leave WAV files behind
+    let _ = fs::remove_file(&temp_wav);
+    let (language, mut segments) = transcription?;
+    glossary.correct_segments(&mut segments);
+
+    // Emit language detection result
+    on_progress(TranscriptionProgress::LanguageDetected {
+        language: language.clone(),
+    });
+
+    // Step 3: Format results
+    on_progress(TranscriptionProgress::GeneratingSubtitles);
+
+    let final_segments = to_subtitle_segments(&segments, 0.0);
+
+    let text = final_segments
+        .iter()
+        .map(|s| s.text.clone())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let srt = generate_srt(&final_segments);
+    let vtt = generate_vtt(&final_segments);
+
+    on_progress(TranscriptionProgress::Complete {
+        subtitle_format: "SRT/VTT".to_string(),
+    });
+
+    Ok(TranscriptionResult {
+        text,
+        subtitles_srt: srt,
+        subtitles_vtt: vtt,
+        language,
+        segments: final_segments,
+        result_id: None,
+    })
+}
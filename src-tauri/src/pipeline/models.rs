@@ -0,0 +1,127 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use sysinfo::System;
+use tracing::warn;
+
+/// Must match `identifier` in tauri.conf.json (Tauri derives app_data_dir from it)
+pub const APP_IDENTIFIER: &str = "com.tauri-whisper.app";
+
+/// App data directory without a Tauri `AppHandle` (used by the headless CLI)
+/// Resolves to the same folder as `app.path().app_data_dir()`
+pub fn default_app_data_dir() -> Result<PathBuf> {
+    let data_dir = dirs::data_dir().context("Failed to get system data directory")?;
+    Ok(data_dir.join(APP_IDENTIFIER))
+}
+
+/// Models directory inside the app data directory (created if missing)
+pub fn models_dir_in(app_data_dir: &Path) -> Result<PathBuf> {
+    let models_dir = app_data_dir.join("models");
+    fs::create_dir_all(&models_dir).context("Failed to create models directory")?;
+
+    Ok(models_dir)
+}
+
+/// Resolve a Whisper model name (e.g. "base") to its downloaded `ggml-*.bin` file
+pub fn whisper_model_path(models_dir: &Path, model_name: &str) -> Result<PathBuf> {
+    let model_path = models_dir.join(format!("ggml-{}.bin", model_name));
+
+    if !model_path.exists() {
+        anyhow::bail!(
+            "Model '{}' not found. Please download it first.",
+            model_name
+        );
+    }
+
+    Ok(model_path)
+}
+
+/// Model name of a `ggml-*.bin` file (inverse of `whisper_model_path`)
+pub fn whisper_model_name(model_path: &Path) -> String {
+    let file_stem = model_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    match file_stem.strip_prefix("ggml-") {
+        Some(model_name) => model_name.to_string(),
+        None => file_stem,
+    }
+}
+
+/// Fixed runtime overhead on top of the weights (compute buffers, KV cache, state)
+const MODEL_RUNTIME_OVERHEAD_MB: u64 = 180;
+
+/// Estimated memory needs of a Whisper model vs. what this machine has free
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelRequirements {
+    pub model_name: String,
+    pub model_size_mb: u64,
+    pub estimated_ram_mb: u64,
+    pub available_ram_mb: u64,
+    pub total_ram_mb: u64,
+    pub fits: bool, // false = loading the model will most likely crash the app
+}
+
+/// Estimate RAM needed to run a model from its file size.
+///
+/// Matches whisper.cpp's published figures closely (tiny ~273MB, base ~388MB,
+/// small ~852MB, medium ~2.1GB, large ~3.9GB) and also works for quantized models.
+fn estimate_model_ram_mb(model_size_mb: u64) -> u64 {
+    model_size_mb * 5 / 4 + MODEL_RUNTIME_OVERHEAD_MB
+}
+
+/// Compare a downloaded model's estimated RAM usage with the available system memory
+pub fn check_model_requirements(model_name: &str, model_path: &Path) -> Result<ModelRequirements> {
+    let model_size_bytes = fs::metadata(model_path)
+        .context("Failed to read model file size")?
+        .len();
+    let model_size_mb = model_size_bytes / (1024 * 1024);
+
+    let mut system = System::new();
+    system.refresh_memory();
+
+    let estimated_ram_mb = estimate_model_ram_mb(model_size_mb);
+    let available_ram_mb = system.available_memory() / (1024 * 1024);
+    let total_ram_mb = system.total_memory() / (1024 * 1024);
+
+    Ok(ModelRequirements {
+        model_name: model_name.to_string(),
+        model_size_mb,
+        estimated_ram_mb,
+        available_ram_mb,
+        total_ram_mb,
+        fits: estimated_ram_mb <= available_ram_mb,
+    })
+}
+
+/// Refuse to load a model that clearly won't fit in the available memory
+/// `ignore_memory_check` lets the user try anyway (e.g. swap is configured)
+pub fn ensure_model_fits(
+    model_name: &str,
+    model_path: &Path,
+    ignore_memory_check: bool,
+) -> Result<()> {
+    let requirements = check_model_requirements(model_name, model_path)?;
+    if requirements.fits {
+        return Ok(());
+    }
+
+    if ignore_memory_check {
+        warn!(
+            "Loading '{}' anyway: needs ~{}MB, {}MB available",
+            model_name, requirements.estimated_ram_mb, requirements.available_ram_mb
+        );
+        return Ok(());
+    }
+
+    anyhow::bail!(
+        "Model '{}' needs about {:.1} GB of RAM but only {:.1} GB is available ({:.1} GB total). \
+         Close other applications or use a smaller model, or enable the memory check override to load it anyway.",
+        model_name,
+        requirements.estimated_ram_mb as f64 / 1024.0,
+        requirements.available_ram_mb as f64 / 1024.0,
+        requirements.total_ram_mb as f64 / 1024.0
+    )
+}
@@ -0,0 +1,321 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::whisper_rs_imp::transcriber::TranscribedSegment;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubtitleSegment {
+    pub index: usize,
+    pub start_time: f64,
+    pub end_time: f64,
+    pub text: String,
+    #[serde(default)]
+    pub avg_probability: Option<f32>,
+    #[serde(default)]
+    pub no_speech_probability: Option<f32>,
+    /// Decoding temperature (above the base one when the fallback ladder retried it)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+}
+
+/// Timing options applied when exporting subtitles
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SubtitleOptions {
+    /// Shift every timestamp by this many seconds (negative = earlier)
+    #[serde(default)]
+    pub offset_seconds: f64,
+    /// Snap timestamps to frame boundaries of this frame rate (e.g. 23.976, 25, 29.97)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frame_rate: Option<f64>,
+}
+
+/// Split a timestamp into (hours, minutes, seconds, fraction) where the fraction
+/// is in `1 / units_per_second` (1000 = ms, 100 = cs).
+/// Rounded once on the total so frame-snapped times (e.g. 1.16s) don't print
+/// one unit early because of float error.
+fn split_timestamp(seconds: f64, units_per_second: u64) -> (u64, u64, u64, u64) {
+    let total_units = (seconds.max(0.0) * units_per_second as f64).round() as u64;
+    let total_seconds = total_units / units_per_second;
+
+    (
+        total_seconds / 3600,
+        (total_seconds % 3600) / 60,
+        total_seconds % 60,
+        total_units % units_per_second,
+    )
+}
+
+/// Format timestamp for SRT (HH:MM:SS,mmm)
+pub fn format_timestamp_srt(seconds: f64) -> String {
+    let (hours, minutes, secs, millis) = split_timestamp(seconds, 1000);
+    format!("{:02}:{:02}:{:02},{:03}", hours, minutes, secs, millis)
+}
+
+/// Format timestamp for VTT (HH:MM:SS.mmm)
+pub fn format_timestamp_vtt(seconds: f64) -> String {
+    let (hours, minutes, secs, millis) = split_timestamp(seconds, 1000);
+    format!("{:02}:{:02}:{:02}.{:03}", hours, minutes, secs, millis)
+}
+
+/// Format timestamp for ASS (H:MM:SS.cc, centiseconds)
+pub fn format_timestamp_ass(seconds: f64) -> String {
+    let (hours, minutes, secs, centis) = split_timestamp(seconds, 100);
+    format!("{}:{:02}:{:02}.{:02}", hours, minutes, secs, centis)
+}
+
+/// Convert Whisper segments to subtitle segments
+/// `offset_sec` shifts every timestamp (used when only a window of the file was transcribed)
+pub fn to_subtitle_segments(
+    segments: &[TranscribedSegment],
+    offset_sec: f64,
+) -> Vec<SubtitleSegment> {
+    segments
+        .iter()
+        .enumerate()
+        .map(|(idx, segment)| SubtitleSegment {
+            index: idx,
+            start_time: segment.start + offset_sec,
+            end_time: segment.end + offset_sec,
+            text: segment.text.clone(),
+            avg_probability: Some(segment.avg_probability),
+            no_speech_probability: Some(segment.no_speech_probability),
+            temperature: Some(segment.temperature),
+        })
+        .collect()
+}
+
+/// Generate SRT subtitle format
+pub fn generate_srt(segments: &[SubtitleSegment]) -> String {
+    let mut srt = String::new();
+    for segment in segments {
+        srt.push_str(&format!("{}\n", segment.index + 1));
+        srt.push_str(&format!(
+            "{} --> {}\n",
+            format_timestamp_srt(segment.start_time),
+            format_timestamp_srt(segment.end_time)
+        ));
+        srt.push_str(&format!("{}\n\n", segment.text.trim()));
+    }
+    srt
+}
+
+/// Generate WebVTT subtitle format
+pub fn generate_vtt(segments: &[SubtitleSegment]) -> String {
+    let mut vtt = String::from("WEBVTT\n\n");
+    for segment in segments {
+        vtt.push_str(&format!(
+            "{} --> {}\n",
+            format_timestamp_vtt(segment.start_time),
+            format_timestamp_vtt(segment.end_time)
+        ));
+        vtt.push_str(&format!("{}\n\n", segment.text.trim()));
+    }
+    vtt
+}
+
+/// Generate Advanced SubStation Alpha (.ass) subtitles with a single default style
+pub fn generate_ass(segments: &[SubtitleSegment]) -> String {
+    let mut ass = String::from(
+        "[Script Info]\n\
+         ScriptType: v4.00+\n\
+         PlayResX: 1920\n\
+         PlayResY: 1080\n\
+         WrapStyle: 0\n\
+         ScaledBorderAndShadow: yes\n\
+         \n\
+         [V4+ Styles]\n\
+         Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, BackColour, \
+         Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, BorderStyle, Outline, \
+         Shadow, Alignment, MarginL, MarginR, MarginV, Encoding\n\
+         Style: Default,Arial,64,&H00FFFFFF,&H000000FF,&H00000000,&H80000000,0,0,0,0,100,100,0,0,1,3,1,2,60,60,50,1\n\
+         \n\
+         [Events]\n\
+         Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text\n",
+    );
+
+    for segment in segments {
+        // "{" starts an override block and line breaks are written as \N in ASS
+        let text = segment
+            .text
+            .trim()
+            .replace('{', "\\{")
+            .replace('}', "\\}")
+            .replace('\n', "\\N");
+
+        ass.push_str(&format!(
+            "Dialogue: 0,{},{},Default,,0,0,0,,{}\n",
+            format_timestamp_ass(segment.start_time),
+            format_timestamp_ass(segment.end_time),
+            text
+        ));
+    }
+    ass
+}
+
+/// Shift and linearly rescale timestamps: `t' = t * scale + offset_sec`.
+///
+/// `scale` fixes progressive drift (e.g. 25 / 23.976 after a frame rate
+/// conversion). Segments pushed entirely before 0 are dropped, the others
+/// are clamped at 0 and renumbered.
+pub fn adjust_segments(
+    segments: &[SubtitleSegment],
+    offset_sec: f64,
+    scale: f64,
+) -> Result<Vec<SubtitleSegment>> {
+    if !offset_sec.is_finite() {
+        anyhow::bail!("Invalid offset: {}", offset_sec);
+    }
+    if !scale.is_finite() || scale <= 0.0 {
+        anyhow::bail!("Scale must be a positive number, got {}", scale);
+    }
+
+    Ok(segments
+        .iter()
+        .filter_map(|segment| {
+            let end_time = segment.end_time * scale + offset_sec;
+            if end_time <= 0.0 {
+                return None;
+            }
+
+            Some(SubtitleSegment {
+                start_time: (segment.start_time * scale + offset_sec).max(0.0),
+                end_time,
+                ..segment.clone()
+            })
+        })
+        .enumerate()
+        .map(|(index, segment)| SubtitleSegment { index, ..segment })
+        .collect())
+}
+
+/// Snap timestamps to the nearest frame boundary of `frame_rate`
+/// Every segment keeps at least one frame of duration
+pub fn snap_to_frames(
+    segments: &[SubtitleSegment],
+    frame_rate: f64,
+) -> Result<Vec<SubtitleSegment>> {
+    if !frame_rate.is_finite() || frame_rate <= 0.0 {
+        anyhow::bail!("Frame rate must be a positive number, got {}", frame_rate);
+    }
+
+    let frame_duration = 1.0 / frame_rate;
+    let snap = |seconds: f64| (seconds * frame_rate).round() / frame_rate;
+
+    Ok(segments
+        .iter()
+        .map(|segment| {
+            let start_time = snap(segment.start_time);
+            let end_time = snap(segment.end_time).max(start_time + frame_duration);
+
+            SubtitleSegment {
+                start_time,
+                end_time,
+                ..segment.clone()
+            }
+        })
+        .collect())
+}
+
+/// Apply export options (offset, then frame snapping) to segments
+pub fn apply_subtitle_options(
+    segments: &[SubtitleSegment],
+    options: &SubtitleOptions,
+) -> Result<Vec<SubtitleSegment>> {
+    let shifted = adjust_segments(segments, options.offset_seconds, 1.0)?;
+
+    match options.frame_rate {
+        Some(frame_rate) => snap_to_frames(&shifted, frame_rate),
+        None => Ok(shifted),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(index: usize, start_time: f64, end_time: f64) -> SubtitleSegment {
+        SubtitleSegment {
+            index,
+            start_time,
+            end_time,
+            text: format!("Segment {}", index),
+            avg_probability: None,
+            no_speech_probability: None,
+            temperature: None,
+        }
+    }
+
+    #[test]
+    fn test_format_timestamps() {
+        assert_eq!(format_timestamp_srt(3723.5), "01:02:03,500");
+        assert_eq!(format_timestamp_vtt(3723.5), "01:02:03.500");
+        assert_eq!(format_timestamp_ass(3723.5), "1:02:03.50");
+        assert_eq!(format_timestamp_srt(-0.2), "00:00:00,000");
+    }
+
+    #[test]
+    fn test_format_rounds_instead_of_truncating() {
+        // 59.9996s rounds up into the next minute
+        assert_eq!(format_timestamp_srt(59.9996), "00:01:00,000");
+        assert_eq!(format_timestamp_ass(59.996), "0:01:00.00");
+    }
+
+    #[test]
+    fn test_snapped_frame_formats_exactly() {
+        // 1.15s snaps to frame 29 at 25 fps (1.16s, stored as 1.1599999...)
+        let snapped = snap_to_frames(&[segment(0, 1.15, 2.0)], 25.0).unwrap();
+
+        assert_eq!(format_timestamp_srt(snapped[0].start_time), "00:00:01,160");
+        assert_eq!(format_timestamp_vtt(snapped[0].start_time), "00:00:01.160");
+        assert_eq!(format_timestamp_ass(snapped[0].start_time), "0:00:01.16");
+    }
+
+    #[test]
+    fn test_adjust_segments_shifts_and_scales() {
+        let segments = [segment(0, 1.0, 2.0), segment(1, 10.0, 12.0)];
+
+        let adjusted = adjust_segments(&segments, 1.5, 2.0).unwrap();
+
+        assert_eq!(adjusted[0].start_time, 3.5);
+        assert_eq!(adjusted[0].end_time, 5.5);
+        assert_eq!(adjusted[1].start_time, 21.5);
+        assert_eq!(adjusted[1].end_time, 25.5);
+    }
+
+    #[test]
+    fn test_adjust_segments_drops_and_clamps_before_zero() {
+        let segments = [
+            segment(0, 0.0, 1.0),
+            segment(1, 1.5, 3.0),
+            segment(2, 4.0, 5.0),
+        ];
+
+        let adjusted = adjust_segments(&segments, -2.0, 1.0).unwrap();
+
+        assert_eq!(adjusted.len(), 2);
+        assert_eq!(adjusted[0].index, 0);
+        assert_eq!(adjusted[0].text, "Segment 1");
+        assert_eq!(adjusted[0].start_time, 0.0);
+        assert_eq!(adjusted[0].end_time, 1.0);
+        assert_eq!(adjusted[1].index, 1);
+    }
+
+    #[test]
+    fn test_adjust_segments_rejects_invalid_values() {
+        let segments = [segment(0, 0.0, 1.0)];
+
+        assert!(adjust_segments(&segments, f64::NAN, 1.0).is_err());
+        assert!(adjust_segments(&segments, 0.0, 0.0).is_err());
+        assert!(adjust_segments(&segments, 0.0, -1.0).is_err());
+    }
+
+    #[test]
+    fn test_snap_to_frames_keeps_one_frame() {
+        // Both ends snap to frame 25 at 25 fps
+        let snapped = snap_to_frames(&[segment(0, 1.01, 1.02)], 25.0).unwrap();
+
+        assert_eq!(format_timestamp_srt(snapped[0].start_time), "00:00:01,000");
+        assert_eq!(format_timestamp_srt(snapped[0].end_time), "00:00:01,040");
+        assert!(snap_to_frames(&[segment(0, 0.0, 1.0)], 0.0).is_err());
+    }
+}
@@ -0,0 +1,235 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use super::audio::convert_audio_with_ffmpeg;
+
+/// Rate of the WAV produced by the conversion step (same as for Whisper)
+const WAV_SAMPLE_RATE: u32 = 16_000;
+
+/// Highest resolution accepted for waveform data
+const MAX_WAVEFORM_SAMPLES_PER_SECOND: u32 = 1_000;
+
+/// Buckets quieter than this RMS level (dBFS) count as silence
+const SILENCE_THRESHOLD_DB: f32 = -40.0;
+
+/// Shorter quiet stretches are pauses between words, not silences
+const MIN_SILENCE_SECONDS: f64 = 0.5;
+
+// Unique temp WAV names, several waveforms can be extracted at once
+static WAVEFORM_COUNTER: AtomicU64 = AtomicU64::new(1);
+
+/// A stretch of audio below the silence threshold (seconds)
+#[derive(Debug, Clone, Serialize)]
+pub struct SilenceRegion {
+    pub start: f64,
+    pub end: f64,
+}
+
+/// Downsampled waveform of a file, `peaks[i]`/`rms[i]` cover
+/// `[i / samples_per_second, (i + 1) / samples_per_second)`
+#[derive(Debug, Clone, Serialize)]
+pub struct AudioWaveform {
+    pub duration_seconds: f64,
+    pub samples_per_second: u32,
+    pub peaks: Vec<f32>, // max absolute amplitude (0.0 - 1.0)
+    pub rms: Vec<f32>,   // RMS amplitude (0.0 - 1.0)
+    pub silences: Vec<SilenceRegion>,
+}
+
+fn amplitude_to_db(amplitude: f32) -> f32 {
+    20.0 * amplitude.max(1e-6).log10()
+}
+
+/// Merge consecutive quiet buckets into silence regions
+fn detect_silences(rms: &[f32], samples_per_second: u32) -> Vec<SilenceRegion> {
+    let bucket_seconds = 1.0 / samples_per_second as f64;
+    let mut silences = Vec::new();
+    let mut silence_start: Option<usize> = None;
+
+    // Extra loud bucket at the end closes a trailing silence
+    for (index, level) in rms.iter().copied().chain(std::iter::once(1.0)).enumerate() {
+        let is_quiet = amplitude_to_db(level) < SILENCE_THRESHOLD_DB;
+
+        match (is_quiet, silence_start) {
+            (true, None) => silence_start = Some(index),
+            (false, Some(start)) => {
+                let duration = (index - start) as f64 * bucket_seconds;
+                if duration >= MIN_SILENCE_SECONDS {
+                    silences.push(SilenceRegion {
+                        start: start as f64 * bucket_seconds,
+                        end: index as f64 * bucket_seconds,
+                    });
+                }
+                silence_start = None;
+            }
+            _ => {}
+        }
+    }
+
+    silences
+}
+
+/// Compute peak/RMS buckets from a 16kHz 16-bit mono WAV, streaming the
+/// samples so long files are never fully loaded in memory
+fn compute_waveform(wav_path: &Path, samples_per_second: u32) -> Result<AudioWaveform> {
+    let mut reader = hound::WavReader::open(wav_path).context("Failed to open WAV file")?;
+    let spec = reader.spec();
+
+    if spec.sample_rate != WAV_SAMPLE_RATE || spec.bits_per_sample != 16 || spec.channels != 1 {
+        anyhow::bail!(
+            "Expected 16kHz 16-bit mono WAV, got {}Hz {}-bit {} channel(s)",
+            spec.sample_rate,
+            spec.bits_per_sample,
+            spec.channels
+        );
+    }
+
+    let total_samples = reader.len() as u64;
+    let bucket_count_estimate =
+        (total_samples * samples_per_second as u64 / WAV_SAMPLE_RATE as u64) as usize + 1;
+    let mut peaks = Vec::with_capacity(bucket_count_estimate);
+    let mut rms = Vec::with_capacity(bucket_count_estimate);
+
+    let mut bucket_peak = 0.0f32;
+    let mut bucket_square_sum = 0.0f64;
+    let mut bucket_count = 0usize;
+
+    for (sample_index, sample) in reader.samples::<i16>().enumerate() {
+        let sample = sample.context("Failed to read WAV samples")? as f32 / 32768.0;
+
+        // Bucket boundaries are computed from the sample position so the
+        // buckets don't drift when 16000 isn't a multiple of the requested rate
+        let bucket = sample_index as u64 * samples_per_second as u64 / WAV_SAMPLE_RATE as u64;
+        if bucket as usize > peaks.len() && bucket_count > 0 {
+            peaks.push(bucket_peak);
+            rms.push((bucket_square_sum / bucket_count as f64).sqrt() as f32);
+            bucket_peak = 0.0;
+            bucket_square_sum = 0.0;
+            bucket_count = 0;
+        }
+
+        bucket_peak = bucket_peak.max(sample.abs());
+        bucket_square_sum += (sample * sample) as f64;
+        bucket_count += 1;
+    }
+
+    // Last partial bucket
+    if bucket_count > 0 {
+        peaks.push(bucket_peak);
+        rms.push((bucket_square_sum / bucket_count as f64).sqrt() as f32);
+    }
+
+    Ok(AudioWaveform {
+        duration_seconds: total_samples as f64 / WAV_SAMPLE_RATE as f64,
+        samples_per_second,
+        silences: detect_silences(&rms, samples_per_second),
+        peaks,
+        rms,
+    })
+}
+
+/// Decode any audio/video file (same ffmpeg conversion as transcription) and
+/// compute its waveform, using `temp_dir` for the intermediate WAV (blocking)
+pub fn extract_waveform(
+    audio_path: &Path,
+    temp_dir: &Path,
+    samples_per_second: u32,
+) -> Result<AudioWaveform> {
+    if samples_per_second == 0 || samples_per_second > MAX_WAVEFORM_SAMPLES_PER_SECOND {
+        anyhow::bail!(
+            "Samples per second must be between 1 and {}",
+            MAX_WAVEFORM_SAMPLES_PER_SECOND
+        );
+    }
+    if !audio_path.exists() {
+        anyhow::bail!("File not found: {}", audio_path.display());
+    }
+
+    fs::create_dir_all(temp_dir).context("Failed to create temp directory")?;
+    let temp_wav = temp_dir.join(format!(
+        "waveform-{}.wav",
+        WAVEFORM_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+
+    let waveform = convert_audio_with_ffmpeg(audio_path, &temp_wav, None)
+        .and_then(|_| compute_waveform(&temp_wav, samples_per_second));
+
+    let _ = fs::remove_file(&temp_wav);
+    waveform
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOUD: f32 = 0.5;
+    const QUIET: f32 = 0.001; // -60 dBFS
+
+    /// `seconds` of `level` RMS at 4 buckets per second
+    fn buckets(rms: &mut Vec<f32>, level: f32, seconds: usize) {
+        rms.resize(rms.len() + seconds * 4, level);
+    }
+
+    #[test]
+    fn test_detect_silences() {
+        let mut rms = Vec::new();
+        buckets(&mut rms, QUIET, 1);
+        buckets(&mut rms, LOUD, 2);
+        rms.push(QUIET); // 0.25s pause between words
+        buckets(&mut rms, LOUD, 1);
+        buckets(&mut rms, QUIET, 2);
+
+        let silences = detect_silences(&rms, 4);
+
+        let regions: Vec<(f64, f64)> = silences.iter().map(|s| (s.start, s.end)).collect();
+        assert_eq!(regions, [(0.0, 1.0), (4.25, 6.25)]);
+    }
+
+    #[test]
+    fn test_detect_silences_without_silence() {
+        assert!(detect_silences(&[LOUD; 20], 4).is_empty());
+        assert!(detect_silences(&[], 4).is_empty());
+    }
+
+    #[test]
+    fn test_compute_waveform() {
+        let wav_path = std::env::temp_dir().join(format!(
+            "tauri-whisper-waveform-test-{}.wav",
+            std::process::id()
+        ));
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: WAV_SAMPLE_RATE,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+
+        // 1s of a square wave at half amplitude, then 1s of silence
+        let mut writer = hound::WavWriter::create(&wav_path, spec).unwrap();
+        for index in 0..WAV_SAMPLE_RATE {
+            writer
+                .write_sample(if index % 2 == 0 { 16_384i16 } else { -16_384 })
+                .unwrap();
+        }
+        for _ in 0..WAV_SAMPLE_RATE {
+            writer.write_sample(0i16).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let waveform = compute_waveform(&wav_path, 4);
+        let _ = fs::remove_file(&wav_path);
+        let waveform = waveform.unwrap();
+
+        assert_eq!(waveform.duration_seconds, 2.0);
+        assert_eq!(waveform.peaks.len(), 8);
+        assert_eq!(waveform.peaks[0], 0.5);
+        assert_eq!(waveform.rms[3], 0.5);
+        assert_eq!(waveform.peaks[4], 0.0);
+
+        let regions: Vec<(f64, f64)> = waveform.silences.iter().map(|s| (s.start, s.end)).collect();
+        assert_eq!(regions, [(1.0, 2.0)]);
+    }
+}
@@ -5,79 +5,385 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
-use vosk::{Model, Recognizer};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tracing::{debug, info, warn};
+use vosk::{CompleteResult, Model, Recognizer, SpeakerModel};
 
-/// Result of Vosk real-time transcription
+use crate::whisper_rs_imp::live_transcriber::MAX_UTTERANCE_SECONDS;
+
+/// Sessions without any chunk for this long are finalized and dropped
+/// (frees the model if the frontend crashed mid-recording)
+pub const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Per-session recognizer options (defaults = plain text, best result only)
+/// NLSML output can be requested but isn't supported yet: vosk-rs parses every
+/// result as JSON, so sessions with `nlsml` set are rejected when started
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VoskSessionOptions {
+    /// Speaker identification model folder (e.g. "vosk-model-spk-0.4"),
+    /// final results then include a speaker embedding vector
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speaker_model_name: Option<String>,
+    /// Number of alternative transcripts for final results (0 = best result only)
+    #[serde(default)]
+    pub max_alternatives: u16,
+    /// Include per-word timings and confidences in final results
+    #[serde(default)]
+    pub words: bool,
+    /// NLSML (XML) output instead of JSON, not supported yet (rejected, see above)
+    #[serde(default)]
+    pub nlsml: bool,
+    /// Translate final results into this language ("en", Whisper's translate task)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_language: Option<String>,
+    /// Whisper model used for the translation (default: the refine model, then "base"),
+    /// must be multilingual
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub translation_model_name: Option<String>,
+    /// Dual-model mode: Whisper model re-transcribing each final result in the
+    /// background, emitted later as a "refined" event with the same `utterance_id`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refine_model_name: Option<String>,
+    /// Load the refine/translation Whisper model even if it likely doesn't fit in memory
+    #[serde(default)]
+    pub ignore_memory_check: bool,
+}
+
+impl VoskSessionOptions {
+    /// True when final results are translated (an empty `target_language` means no translation)
+    pub fn is_translating(&self) -> bool {
+        self.target_language
+            .as_deref()
+            .is_some_and(|target| !target.trim().is_empty())
+    }
+}
+
+/// A recognized word with its timings (seconds since session start)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VoskWord {
+    pub word: String,
+    pub start: f32,
+    pub end: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f32>, // not reported for alternatives
+}
+
+/// One of the N-best transcripts of a final result
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VoskAlternative {
+    pub text: String,
+    pub confidence: f32,
+}
+
+/// Speaker embedding of a final result (compare vectors to tell speakers apart)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VoskSpeakerInfo {
+    pub vector: Vec<f32>,
+    pub frames: u16,
+}
+
+/// Result of Vosk real-time transcription
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct VoskTranscriptionResult {
     pub text: String,
     pub is_partial: bool,
+    /// Utterance this result belongs to (partials share the id of their final result)
+    pub utterance_id: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub words: Option<Vec<VoskWord>>, // final results with `words` enabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alternatives: Option<Vec<VoskAlternative>>, // final results with `max_alternatives` > 0
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speaker: Option<VoskSpeakerInfo>, // final results with a speaker model
+}
+
+/// Active configuration of a Vosk session, returned to the frontend
+#[derive(Debug, Clone, Serialize)]
+pub struct VoskSessionInfo {
+    pub session_id: String,
+    pub model_name: String,
+    pub sample_rate: f32,
+    pub options: VoskSessionOptions,
+    pub started_at_ms: u64,
+    pub last_activity_ms: u64,
+    pub idle_seconds: f64,
+    pub chunks_processed: u64,
+}
+
+/// A session closed by the manager rather than by the frontend
+#[derive(Debug, Clone, Serialize)]
+pub struct ClosedVoskSession {
+    pub session_id: String,
+    pub final_text: String,
+    pub idle_seconds: f64,
+}
+
+/// Whisper's re-transcription of a final result (dual-model mode),
+/// payload of "vosk-utterance-refined" events
+#[derive(Debug, Clone, Serialize)]
+pub struct RefinedVoskUtterance {
+    pub session_id: String,
+    pub utterance_id: u64,
+    pub original_text: String, // Vosk's final text
+    pub text: String,          // Whisper's text (Vosk's text if refinement failed)
+    pub language: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Whisper's translation of a final result (sessions with a `target_language`),
+/// payload of "vosk-utterance-translated" events
+#[derive(Debug, Clone, Serialize)]
+pub struct TranslatedVoskUtterance {
+    pub session_id: String,
+    pub utterance_id: u64,
+    pub original_text: String,           // Vosk's final text
+    pub translated_text: Option<String>, // None if the translation failed
+    pub error: Option<String>,
+}
+
+impl VoskTranscriptionResult {
+    fn partial(text: String) -> Self {
+        Self {
+            text,
+            is_partial: true,
+            ..Default::default()
+        }
+    }
+
+    /// Convert a final recognizer result (single or N-best) to our result type
+    fn from_complete(result: CompleteResult<'_>) -> Self {
+        match result {
+            CompleteResult::Single(single) => {
+                let words = (!single.result.is_empty()).then(|| {
+                    single
+                        .result
+                        .iter()
+                        .map(|word| VoskWord {
+                            word: word.word.to_string(),
+                            start: word.start,
+                            end: word.end,
+                            confidence: Some(word.conf),
+                        })
+                        .collect()
+                });
+
+                Self {
+                    text: single.text.to_string(),
+                    is_partial: false,
+                    utterance_id: 0, // set by the session
+                    words,
+                    alternatives: None,
+                    speaker: single.speaker_info.map(|info| VoskSpeakerInfo {
+                        vector: info.vector,
+                        frames: info.frames,
+                    }),
+                }
+            }
+            CompleteResult::Multiple(multiple) => {
+                // Alternatives are sorted by confidence, the first one is the best guess
+                let best = multiple.alternatives.first();
+                let words = best.filter(|alt| !alt.result.is_empty()).map(|alt| {
+                    alt.result
+                        .iter()
+                        .map(|word| VoskWord {
+                            word: word.word.to_string(),
+                            start: word.start,
+                            end: word.end,
+                            confidence: None,
+                        })
+                        .collect()
+                });
+
+                Self {
+                    text: best.map(|alt| alt.text.to_string()).unwrap_or_default(),
+                    is_partial: false,
+                    utterance_id: 0, // set by the session
+                    words,
+                    alternatives: Some(
+                        multiple
+                            .alternatives
+                            .iter()
+                            .map(|alt| VoskAlternative {
+                                text: alt.text.to_string(),
+                                confidence: alt.confidence,
+                            })
+                            .collect(),
+                    ),
+                    speaker: None,
+                }
+            }
+        }
+    }
 }
 
 /// Live Vosk session - maintains recognizer AND model state across audio chunks
 /// Model and Recognizer must live together (recognizer borrows from model)
 pub struct VoskLiveSession {
-    model: Arc<Model>,       // Model must stay alive for recognizer
-    recognizer: Recognizer,  // Recognizer borrows from model
+    model: Arc<Model>,                        // Model must stay alive for recognizer
+    speaker_model: Option<Arc<SpeakerModel>>, // Same for the optional speaker model
+    recognizer: Recognizer,                   // Recognizer borrows from model
     sample_rate: f32,
+    model_name: String,
+    options: VoskSessionOptions,
+    started_at_ms: u64,
+    last_activity_ms: u64,
+    last_activity: Instant, // monotonic, used for the idle timeout
+    chunks_processed: u64,
+    utterance_id: u64,                    // id of the utterance being recognized
+    utterance_audio: Vec<i16>, // current utterance, only buffered when translating/refining
+    finished_utterance: Option<Vec<i16>>, // audio of the last final result, until taken
 }
 
 impl VoskLiveSession {
-    /// Create new Vosk session with model and sample rate
-    pub fn new(model_path: &PathBuf, sample_rate: f32) -> Result<Self> {
-        println!("🔄 [Vosk] Creating session with model: {:?}", model_path);
+    /// Create new Vosk session with model, sample rate and recognizer options
+    /// `speaker_model_path` must be given when `options.speaker_model_name` is set
+    pub fn new(
+        model_path: &PathBuf,
+        sample_rate: f32,
+        options: VoskSessionOptions,
+        speaker_model_path: Option<&PathBuf>,
+    ) -> Result<Self> {
+        debug!("Creating session with model: {:?}", model_path);
+
+        // vosk-rs parses every result as JSON, XML results can't be read back
+        if options.nlsml {
+            anyhow::bail!("NLSML output is not supported (results are parsed as JSON)");
+        }
 
-        let model_path_str = model_path
-            .to_str()
-            .context("Invalid model path encoding")?;
+        let model_path_str = model_path.to_str().context("Invalid model path encoding")?;
 
         // Load model
-        let model = Model::new(model_path_str)
-            .ok_or_else(|| anyhow::anyhow!("Failed to load Vosk model from path: {}", model_path_str))?;
+        let model = Model::new(model_path_str).ok_or_else(|| {
+            anyhow::anyhow!("Failed to load Vosk model from path: {}", model_path_str)
+        })?;
 
         let model_arc = Arc::new(model);
 
         // Create recognizer (borrows from model)
         // Safety: We keep model alive in the struct, so recognizer reference is valid
-        let recognizer = unsafe {
+        let mut recognizer = unsafe {
             let model_ptr = Arc::as_ptr(&model_arc);
             let model_ref = &*model_ptr;
-            Recognizer::new(model_ref, sample_rate)
-                .ok_or_else(|| anyhow::anyhow!("Failed to create Vosk recognizer for sample rate: {}", sample_rate))?
+            Recognizer::new(model_ref, sample_rate).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Failed to create Vosk recognizer for sample rate: {}",
+                    sample_rate
+                )
+            })?
+        };
+
+        recognizer.set_max_alternatives(options.max_alternatives);
+        recognizer.set_words(options.words);
+
+        // Load speaker model (kept alive in the struct like the main model)
+        let speaker_model = match speaker_model_path {
+            Some(path) => {
+                let path_str = path
+                    .to_str()
+                    .context("Invalid speaker model path encoding")?;
+                let speaker_model = SpeakerModel::new(path_str).ok_or_else(|| {
+                    anyhow::anyhow!("Failed to load Vosk speaker model from path: {}", path_str)
+                })?;
+                let speaker_model = Arc::new(speaker_model);
+                recognizer.set_speaker_model(&speaker_model);
+                Some(speaker_model)
+            }
+            None => None,
         };
 
-        println!("✅ [Vosk] Session created successfully");
+        let model_name = model_path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        let started_at_ms = now_ms();
+
+        info!("Session created successfully ({:?})", options);
 
         Ok(Self {
             model: model_arc,
+            speaker_model,
             recognizer,
             sample_rate,
+            model_name,
+            options,
+            started_at_ms,
+            last_activity_ms: started_at_ms,
+            last_activity: Instant::now(),
+            chunks_processed: 0,
+            utterance_id: 1,
+            utterance_audio: Vec::new(),
+            finished_utterance: None,
         })
     }
 
+    /// Describe the session configuration
+    pub fn info(&self, session_id: &str) -> VoskSessionInfo {
+        VoskSessionInfo {
+            session_id: session_id.to_string(),
+            model_name: self.model_name.clone(),
+            sample_rate: self.sample_rate,
+            options: self.options.clone(),
+            started_at_ms: self.started_at_ms,
+            last_activity_ms: self.last_activity_ms,
+            idle_seconds: self.idle_time().as_secs_f64(),
+            chunks_processed: self.chunks_processed,
+        }
+    }
+
+    /// Final results are translated or refined by Whisper, which needs their audio
+    fn keeps_utterance_audio(&self) -> bool {
+        self.options.is_translating() || self.options.refine_model_name.is_some()
+    }
+
+    /// Audio of the last finalized utterance (translation or refinement enabled only)
+    pub fn take_finished_utterance(&mut self) -> Option<Vec<i16>> {
+        self.finished_utterance.take()
+    }
+
+    /// Time since the last audio chunk (or since creation)
+    pub fn idle_time(&self) -> Duration {
+        self.last_activity.elapsed()
+    }
+
     /// Process audio chunk and return transcription result
     /// Follows vosk-rs example pattern: check speech detection, use result() or partial_result()
     pub fn process_chunk(&mut self, pcm_data: &[i16]) -> VoskTranscriptionResult {
+        self.chunks_processed += 1;
+        self.last_activity = Instant::now();
+        self.last_activity_ms = now_ms();
+
+        if self.keeps_utterance_audio() {
+            self.utterance_audio.extend_from_slice(pcm_data);
+
+            let max_samples = (MAX_UTTERANCE_SECONDS * self.sample_rate) as usize;
+            if self.utterance_audio.len() > max_samples {
+                let excess = self.utterance_audio.len() - max_samples;
+                self.utterance_audio.drain(..excess);
+            }
+        }
+
         // Feed audio to recognizer
         // accept_waveform returns Result<DecodingState, AcceptWaveformError>
         // DecodingState::Finalized means speech segment ended
-        match self.recognizer.accept_waveform(pcm_data) {
+        let mut result = match self.recognizer.accept_waveform(pcm_data) {
             Ok(vosk::DecodingState::Finalized) => {
                 // Speech segment ended - get FINAL result
-                let result = self.recognizer.result();
-                if let Some(single) = result.single() {
-                    let text = single.text.to_string();
-                    println!("✅ [Vosk] Final: {}", text);
-                    VoskTranscriptionResult {
-                        text,
-                        is_partial: false,
-                    }
-                } else {
-                    VoskTranscriptionResult {
-                        text: String::new(),
-                        is_partial: false,
-                    }
-                }
+                let result = VoskTranscriptionResult::from_complete(self.recognizer.result());
+                info!("Final: {}", result.text);
+
+                // Hand the utterance over to Whisper (nothing to translate/refine if empty)
+                let utterance = std::mem::take(&mut self.utterance_audio);
+                self.finished_utterance = (!result.text.is_empty()).then_some(utterance);
+
+                result
             }
             Ok(vosk::DecodingState::Running) => {
                 // Still speaking - get PARTIAL result
@@ -85,39 +391,39 @@ impl VoskLiveSession {
                 let text = partial.partial.to_string();
 
                 if !text.is_empty() {
-                    println!("📝 [Vosk] Partial: {}", text);
+                    debug!("Partial: {}", text);
                 }
 
-                VoskTranscriptionResult {
-                    text,
-                    is_partial: true,
-                }
+                VoskTranscriptionResult::partial(text)
             }
             Ok(vosk::DecodingState::Failed) | Err(_) => {
                 // Decoding failed or error - return empty partial
-                println!("⚠️ [Vosk] Decoding failed or error");
-                VoskTranscriptionResult {
-                    text: String::new(),
-                    is_partial: true,
-                }
+                warn!("Decoding failed or error");
+                VoskTranscriptionResult::partial(String::new())
             }
+        };
+
+        result.utterance_id = self.utterance_id;
+        if !result.is_partial {
+            self.utterance_id += 1;
         }
+
+        result
     }
 
     /// Finalize session and get final transcription
     /// Call this when recording is complete
     pub fn finalize(&mut self) -> String {
-        println!("🔚 [Vosk] Finalizing session");
-        let final_result = self.recognizer.final_result();
+        debug!("Finalizing session");
+        let final_result = VoskTranscriptionResult::from_complete(self.recognizer.final_result());
 
-        if let Some(single) = final_result.single() {
-            let text = single.text.to_string();
-            println!("✅ [Vosk] Final result: {}", text);
-            text
+        if final_result.text.is_empty() {
+            warn!("No final result");
         } else {
-            println!("⚠️ [Vosk] No final result");
-            String::new()
+            info!("Final result: {}", final_result.text);
         }
+
+        final_result.text
     }
 }
 
@@ -125,6 +431,7 @@ impl VoskLiveSession {
 pub struct VoskSessionManager {
     sessions: HashMap<String, VoskLiveSession>,
     next_id: u64,
+    idle_timeout: Duration,
 }
 
 impl VoskSessionManager {
@@ -132,44 +439,146 @@ impl VoskSessionManager {
         Self {
             sessions: HashMap::new(),
             next_id: 1,
+            idle_timeout: DEFAULT_IDLE_TIMEOUT,
         }
     }
 
     /// Start new Vosk session
-    pub fn start_session(&mut self, model_path: &PathBuf, sample_rate: f32) -> Result<String> {
-        let session = VoskLiveSession::new(model_path, sample_rate)?;
+    pub fn start_session(
+        &mut self,
+        model_path: &PathBuf,
+        sample_rate: f32,
+        options: VoskSessionOptions,
+        speaker_model_path: Option<&PathBuf>,
+    ) -> Result<String> {
+        let session = VoskLiveSession::new(model_path, sample_rate, options, speaker_model_path)?;
         let session_id = format!("vosk-{}", self.next_id);
         self.next_id += 1;
 
         self.sessions.insert(session_id.clone(), session);
-        println!("🎙️ [Vosk] Session started: {}", session_id);
+        info!("Session started: {}", session_id);
 
         Ok(session_id)
     }
 
     /// Process chunk in existing session
-    pub fn process_chunk(&mut self, session_id: &str, pcm_data: &[i16]) -> Result<VoskTranscriptionResult> {
-        let session = self.sessions
+    pub fn process_chunk(
+        &mut self,
+        session_id: &str,
+        pcm_data: &[i16],
+    ) -> Result<VoskTranscriptionResult> {
+        let session = self
+            .sessions
             .get_mut(session_id)
             .ok_or_else(|| anyhow::anyhow!("Session not found: {}", session_id))?;
 
         Ok(session.process_chunk(pcm_data))
     }
 
+    /// Take the audio of the session's last finalized utterance, if any
+    pub fn take_finished_utterance(&mut self, session_id: &str) -> Option<Vec<i16>> {
+        self.sessions.get_mut(session_id)?.take_finished_utterance()
+    }
+
     /// End session and get final result
     pub fn end_session(&mut self, session_id: &str) -> Result<String> {
-        let mut session = self.sessions
+        let mut session = self
+            .sessions
             .remove(session_id)
             .ok_or_else(|| anyhow::anyhow!("Session not found: {}", session_id))?;
 
         let final_text = session.finalize();
-        println!("🛑 [Vosk] Session ended: {}", session_id);
+        info!("Session ended: {}", session_id);
 
         Ok(final_text)
     }
 
+    /// Get the configuration of an active session
+    pub fn session_info(&self, session_id: &str) -> Result<VoskSessionInfo> {
+        let session = self
+            .sessions
+            .get(session_id)
+            .ok_or_else(|| anyhow::anyhow!("Session not found: {}", session_id))?;
+
+        Ok(session.info(session_id))
+    }
+
     /// Get active session count
     pub fn active_sessions(&self) -> usize {
         self.sessions.len()
     }
+
+    /// Describe every active session (oldest first)
+    pub fn list_sessions(&self) -> Vec<VoskSessionInfo> {
+        let mut sessions: Vec<VoskSessionInfo> = self
+            .sessions
+            .iter()
+            .map(|(session_id, session)| session.info(session_id))
+            .collect();
+        sessions.sort_by_key(|info| info.started_at_ms);
+        sessions
+    }
+
+    pub fn idle_timeout(&self) -> Duration {
+        self.idle_timeout
+    }
+
+    pub fn set_idle_timeout(&mut self, idle_timeout: Duration) -> Result<()> {
+        if idle_timeout.is_zero() {
+            anyhow::bail!("Idle timeout must be greater than 0");
+        }
+
+        self.idle_timeout = idle_timeout;
+        info!("Idle timeout set to {:?}", idle_timeout);
+        Ok(())
+    }
+
+    /// Finalize and drop the sessions matching `should_close`
+    fn close_sessions_where(
+        &mut self,
+        should_close: impl Fn(&VoskLiveSession) -> bool,
+    ) -> Vec<ClosedVoskSession> {
+        let session_ids: Vec<String> = self
+            .sessions
+            .iter()
+            .filter(|(_, session)| should_close(session))
+            .map(|(session_id, _)| session_id.clone())
+            .collect();
+
+        session_ids
+            .into_iter()
+            .filter_map(|session_id| {
+                let mut session = self.sessions.remove(&session_id)?;
+                let idle_seconds = session.idle_time().as_secs_f64();
+                let final_text = session.finalize();
+                Some(ClosedVoskSession {
+                    session_id,
+                    final_text,
+                    idle_seconds,
+                })
+            })
+            .collect()
+    }
+
+    /// Finalize and drop sessions idle for longer than the idle timeout
+    pub fn expire_idle_sessions(&mut self) -> Vec<ClosedVoskSession> {
+        let idle_timeout = self.idle_timeout;
+        let expired = self.close_sessions_where(|session| session.idle_time() > idle_timeout);
+
+        for session in &expired {
+            warn!(
+                "Session {} expired after {:.0}s idle",
+                session.session_id, session.idle_seconds
+            );
+        }
+
+        expired
+    }
+
+    /// Finalize and drop every session
+    pub fn close_all_sessions(&mut self) -> Vec<ClosedVoskSession> {
+        let closed = self.close_sessions_where(|_| true);
+        info!("Force-closed {} session(s)", closed.len());
+        closed
+    }
 }
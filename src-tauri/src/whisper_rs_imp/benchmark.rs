@@ -0,0 +1,198 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use sysinfo::{get_current_pid, ProcessRefreshKind, ProcessesToUpdate, System};
+use tracing::info;
+use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
+
+use crate::pipeline::models::ensure_model_fits;
+
+/// Length of the synthesized benchmark sample.
+/// 30s = exactly one Whisper encoder window, so the encoder cost is representative.
+const BENCHMARK_AUDIO_SECONDS: usize = 30;
+const SAMPLE_RATE: usize = 16_000;
+const MEMORY_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Result of benchmarking a single Whisper model on this machine
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchmarkResult {
+    pub model_name: String,
+    pub load_time_ms: u64,
+    pub transcription_time_ms: u64,
+    pub audio_duration_sec: f64,
+    pub realtime_factor: f64, // transcription time / audio duration (< 1.0 = faster than realtime)
+    pub peak_memory_mb: u64,  // peak resident memory of the app process during the run
+    pub thread_count: usize,
+}
+
+/// Synthesize a speech-like benchmark sample (16kHz mono f32).
+///
+/// Alternates short "syllables" (a pitch-gliding fundamental plus formant-like
+/// harmonics, amplitude modulated) with short pauses, so the decoder does real
+/// work instead of immediately bailing out on silence.
+fn synthesize_benchmark_audio() -> Vec<f32> {
+    let total_samples = BENCHMARK_AUDIO_SECONDS * SAMPLE_RATE;
+    let syllable_len = SAMPLE_RATE / 4; // 250ms
+    let pause_len = SAMPLE_RATE / 10; // 100ms
+    let cycle_len = syllable_len + pause_len;
+    let formants = [(700.0, 0.5), (1200.0, 0.3), (2600.0, 0.15)];
+
+    (0..total_samples)
+        .map(|i| {
+            let position_in_cycle = i % cycle_len;
+            if position_in_cycle >= syllable_len {
+                return 0.0;
+            }
+
+            let t = i as f32 / SAMPLE_RATE as f32;
+            let syllable_index = (i / cycle_len) as f32;
+            let syllable_progress = position_in_cycle as f32 / syllable_len as f32;
+
+            // Fundamental glides between ~110Hz and ~220Hz like intonation
+            let fundamental = 110.0 + 110.0 * (0.5 + 0.5 * (syllable_index * 0.7).sin());
+            let mut sample = (std::f32::consts::TAU * fundamental * t).sin();
+            for (frequency, gain) in formants {
+                sample += gain * (std::f32::consts::TAU * frequency * t).sin();
+            }
+
+            // Smooth attack/release envelope per syllable
+            let envelope = (std::f32::consts::PI * syllable_progress).sin();
+            0.2 * envelope * sample
+        })
+        .collect()
+}
+
+/// Sample this process' resident memory in the background and keep the maximum
+struct PeakMemorySampler {
+    stop: Arc<AtomicBool>,
+    peak_bytes: Arc<AtomicU64>,
+    handle: Option<thread::JoinHandle<()>>,
+}
+
+impl PeakMemorySampler {
+    fn start() -> Result<Self> {
+        let pid = get_current_pid().map_err(|e| anyhow::anyhow!("Failed to get PID: {}", e))?;
+        let stop = Arc::new(AtomicBool::new(false));
+        let peak_bytes = Arc::new(AtomicU64::new(0));
+
+        let handle = thread::spawn({
+            let stop = stop.clone();
+            let peak_bytes = peak_bytes.clone();
+            move || {
+                let mut system = System::new();
+                loop {
+                    system.refresh_processes_specifics(
+                        ProcessesToUpdate::Some(&[pid]),
+                        false,
+                        ProcessRefreshKind::nothing().with_memory(),
+                    );
+                    if let Some(process) = system.process(pid) {
+                        peak_bytes.fetch_max(process.memory(), Ordering::Relaxed);
+                    }
+                    if stop.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    thread::sleep(MEMORY_POLL_INTERVAL);
+                }
+            }
+        });
+
+        Ok(Self {
+            stop,
+            peak_bytes,
+            handle: Some(handle),
+        })
+    }
+
+    /// Stop sampling and return the peak resident memory in bytes
+    fn finish(mut self) -> u64 {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+        self.peak_bytes.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for PeakMemorySampler {
+    fn drop(&mut self) {
+        // Make sure the sampler thread exits even if the benchmark bailed out early
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Benchmark a Whisper model: load time, realtime factor and peak memory.
+///
+/// Uses fixed, fast decoding settings (greedy, best_of 1, all cores) so results
+/// are comparable between models and machines.
+/// The model is refused if it won't fit in memory, unless `ignore_memory_check` is set.
+pub fn benchmark_model(
+    model_name: &str,
+    model_path: &Path,
+    ignore_memory_check: bool,
+) -> Result<BenchmarkResult> {
+    ensure_model_fits(model_name, model_path, ignore_memory_check)?;
+
+    let samples = synthesize_benchmark_audio();
+    let audio_duration_sec = samples.len() as f64 / SAMPLE_RATE as f64;
+    let thread_count = num_cpus::get();
+
+    info!("Benchmarking model '{}'", model_name);
+
+    let memory_sampler = PeakMemorySampler::start()?;
+
+    // --- 1️⃣ Model load ---
+    let load_start = Instant::now();
+    let ctx = WhisperContext::new_with_params(
+        model_path.to_str().context("Invalid model path")?,
+        WhisperContextParameters::default(),
+    )
+    .context("Failed to load Whisper model")?;
+    let load_time = load_start.elapsed();
+
+    let mut state = ctx
+        .create_state()
+        .context("Failed to create Whisper state")?;
+
+    // --- 2️⃣ Transcription ---
+    let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+    params.set_language(Some("en"));
+    params.set_n_threads(thread_count as i32);
+    params.set_print_progress(false);
+    params.set_print_special(false);
+    params.set_print_realtime(false);
+    params.set_print_timestamps(false);
+    params.set_temperature(0.0);
+    params.set_no_context(true);
+
+    let transcription_start = Instant::now();
+    state
+        .full(params, &samples)
+        .context("Benchmark transcription failed")?;
+    let transcription_time = transcription_start.elapsed();
+
+    let peak_memory_bytes = memory_sampler.finish();
+    let realtime_factor = transcription_time.as_secs_f64() / audio_duration_sec;
+
+    info!(
+        "'{}': load {}ms, RTF {:.2}, peak {}MB",
+        model_name,
+        load_time.as_millis(),
+        realtime_factor,
+        peak_memory_bytes / (1024 * 1024)
+    );
+
+    Ok(BenchmarkResult {
+        model_name: model_name.to_string(),
+        load_time_ms: load_time.as_millis() as u64,
+        transcription_time_ms: transcription_time.as_millis() as u64,
+        audio_duration_sec,
+        realtime_factor,
+        peak_memory_mb: peak_memory_bytes / (1024 * 1024),
+        thread_count,
+    })
+}
@@ -0,0 +1,108 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tracing::{debug, info};
+use whisper_rs::{WhisperContext, WhisperContextParameters};
+
+use super::transcriber::load_wav_samples;
+use crate::pipeline::models::{ensure_model_fits, whisper_model_name};
+
+/// A single language candidate returned by Whisper's language detection pass
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LanguageCandidate {
+    pub code: String, // e.g. "en", "fr"
+    pub name: String, // e.g. "english", "french"
+    pub probability: f32,
+}
+
+/// Result of a standalone language detection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LanguageDetectionResult {
+    pub language: String,
+    pub candidates: Vec<LanguageCandidate>,
+}
+
+/// Detect the spoken language of a WAV file without transcribing it.
+///
+/// Requirements:
+/// - WAV must be 16kHz, 16-bit PCM (only the first 30s are used by Whisper).
+/// - Model must be multilingual (`.en` models have no language tokens).
+///
+/// Runs the mel spectrogram + `lang_detect` pass only, which is much cheaper
+/// than a full decode.
+///
+/// Returns the detected language and the `top_n` most probable candidates.
+/// The model is refused if it won't fit in memory, unless `ignore_memory_check` is set.
+pub fn detect_language_from_wav(
+    model_path: &Path,
+    wav_path: &Path,
+    top_n: usize,
+    ignore_memory_check: bool,
+) -> Result<LanguageDetectionResult> {
+    // --- 1️⃣ Load audio ---
+    let samples = load_wav_samples(wav_path)?;
+    if samples.is_empty() {
+        anyhow::bail!("Audio file contains no samples");
+    }
+
+    // --- 2️⃣ Load Whisper model ---
+    let model_name = whisper_model_name(model_path);
+    ensure_model_fits(&model_name, model_path, ignore_memory_check)?;
+
+    let ctx = WhisperContext::new_with_params(
+        model_path.to_str().context("Invalid model path")?,
+        WhisperContextParameters::default(),
+    )
+    .context("Failed to load Whisper model")?;
+
+    if !ctx.is_multilingual() {
+        anyhow::bail!(
+            "Model is English-only and cannot detect languages. Use a multilingual model instead."
+        );
+    }
+
+    let mut state = ctx
+        .create_state()
+        .context("Failed to create Whisper state")?;
+
+    // --- 3️⃣ Compute mel spectrogram + run detection ---
+    let num_threads = num_cpus::get();
+    debug!("Detecting language with {} threads", num_threads);
+
+    state
+        .pcm_to_mel(&samples, num_threads)
+        .context("Failed to compute mel spectrogram")?;
+
+    let (lang_id, probabilities) = state
+        .lang_detect(0, num_threads)
+        .context("Language detection failed")?;
+
+    // --- 4️⃣ Rank candidates ---
+    let mut candidates: Vec<LanguageCandidate> = probabilities
+        .iter()
+        .enumerate()
+        .filter_map(|(id, probability)| {
+            let code = whisper_rs::get_lang_str(id as i32)?;
+            let name = whisper_rs::get_lang_str_full(id as i32).unwrap_or(code);
+            Some(LanguageCandidate {
+                code: code.to_string(),
+                name: name.to_string(),
+                probability: *probability,
+            })
+        })
+        .collect();
+
+    candidates.sort_by(|a, b| b.probability.total_cmp(&a.probability));
+    candidates.truncate(top_n);
+
+    let language = whisper_rs::get_lang_str(lang_id)
+        .unwrap_or("unknown")
+        .to_string();
+
+    info!("Detected language: {}", language);
+
+    Ok(LanguageDetectionResult {
+        language,
+        candidates,
+    })
+}
@@ -1,16 +1,29 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
+use tracing::{debug, info};
 use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
 
+use crate::pipeline::models::{ensure_model_fits, whisper_model_name};
+
+/// Whisper's translate task always translates into English
+pub const WHISPER_TRANSLATION_TARGET: &str = "en";
+
+/// Longest utterance translated/re-transcribed in one pass, older audio is dropped (seconds)
+/// Vosk sessions keep the same amount of utterance audio
+pub const MAX_UTTERANCE_SECONDS: f32 = 30.0;
+
 /// Result of a live transcription chunk
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LiveTranscriptionResult {
     pub text: String,
     pub language: String,
     pub segments: Vec<TranscriptionSegment>,
+    /// `text` in the requested target language (when translation is enabled)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub translated_text: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -36,17 +49,28 @@ impl LiveTranscriptionContext {
     }
 
     /// Load or reuse the Whisper context
-    pub fn get_or_load(&mut self, model_path: &PathBuf) -> Result<&WhisperContext> {
+    /// A new model is only loaded if it fits in memory (or `ignore_memory_check` is set)
+    pub fn get_or_load(
+        &mut self,
+        model_path: &PathBuf,
+        ignore_memory_check: bool,
+    ) -> Result<&WhisperContext> {
         // If context exists and model path matches, reuse it
         if let Some(existing_path) = &self.model_path {
             if existing_path == model_path && self.context.is_some() {
-                println!("🔄 [LiveTranscription] Reusing existing Whisper context");
+                debug!("Reusing existing Whisper context");
                 return Ok(self.context.as_ref().unwrap());
             }
         }
 
+        // Drop the previous model first, it no longer counts against the available memory
+        self.context = None;
+        self.model_path = None;
+        let model_name = whisper_model_name(model_path);
+        ensure_model_fits(&model_name, model_path, ignore_memory_check)?;
+
         // Load new context
-        println!("🔄 [LiveTranscription] Loading Whisper model from: {:?}", model_path);
+        debug!("Loading Whisper model from: {:?}", model_path);
         let ctx = WhisperContext::new_with_params(
             model_path.to_str().context("Invalid model path")?,
             WhisperContextParameters::default(),
@@ -72,7 +96,7 @@ pub fn convert_webm_to_wav(webm_data: &[u8], output_path: &PathBuf) -> Result<()
     std::fs::write(&input_path, webm_data).context("Failed to write temp WebM file")?;
 
     // Run ffmpeg to convert WebM → WAV 16kHz mono
-    println!("🎵 [LiveTranscription] Converting WebM to WAV 16kHz mono");
+    debug!("Converting WebM to WAV 16kHz mono");
 
     // TODO: Use ffmpeg-sidecar to auto-download/bundle ffmpeg instead of relying on system installation
     // Currently requires user to have ffmpeg installed on their system
@@ -100,22 +124,169 @@ pub fn convert_webm_to_wav(webm_data: &[u8], output_path: &PathBuf) -> Result<()
         anyhow::bail!("FFmpeg conversion failed: {}", stderr);
     }
 
-    println!("✅ [LiveTranscription] Audio conversion successful");
+    info!("Audio conversion successful");
     Ok(())
 }
 
+/// Check the target language of a live session.
+/// Returns true when a translation pass is needed (None/"" = original text only).
+///
+/// Whisper can only translate into English, and English-only models (`*.en`)
+/// can't translate at all.
+pub fn translation_requested(target_language: Option<&str>, model_path: &Path) -> Result<bool> {
+    let Some(target) = target_language.map(str::trim).filter(|t| !t.is_empty()) else {
+        return Ok(false);
+    };
+
+    if !target.eq_ignore_ascii_case(WHISPER_TRANSLATION_TARGET) {
+        anyhow::bail!(
+            "Unsupported target language '{}': Whisper can only translate into English (\"{}\")",
+            target,
+            WHISPER_TRANSLATION_TARGET
+        );
+    }
+
+    let model_file = model_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    if model_file.contains(".en.") {
+        anyhow::bail!(
+            "Model '{}' is English-only and can't translate, use a multilingual model",
+            model_file
+        );
+    }
+
+    Ok(true)
+}
+
+/// Linear resampling of 16-bit PCM to Whisper's 16kHz
+fn resample_to_16k(samples: &[i16], sample_rate: f32) -> Vec<f32> {
+    let samples_f32: Vec<f32> = samples.iter().map(|s| *s as f32 / 32768.0).collect();
+    if (sample_rate - 16_000.0).abs() < 1.0 || samples_f32.is_empty() {
+        return samples_f32;
+    }
+
+    let ratio = sample_rate as f64 / 16_000.0;
+    let output_len = (samples_f32.len() as f64 / ratio) as usize;
+    let last = samples_f32.len() - 1;
+
+    (0..output_len)
+        .map(|i| {
+            let position = i as f64 * ratio;
+            let index = (position as usize).min(last);
+            let next = (index + 1).min(last);
+            let fraction = (position - index as f64) as f32;
+            samples_f32[index] * (1.0 - fraction) + samples_f32[next] * fraction
+        })
+        .collect()
+}
+
+/// Output of one Whisper pass over a live buffer
+struct LivePass {
+    text: String,
+    language: String,
+    segments: Vec<TranscriptionSegment>,
+}
+
+/// Run Whisper on 16kHz mono samples with the fast live settings.
+/// `translate` switches to the translate task (output in English).
+fn run_live_pass(
+    context_manager: &Arc<Mutex<LiveTranscriptionContext>>,
+    model_path: &PathBuf,
+    samples: &[f32],
+    language: &str,
+    translate: bool,
+    ignore_memory_check: bool,
+) -> Result<LivePass> {
+    // Get or load Whisper context
+    let mut ctx_manager = context_manager
+        .lock()
+        .map_err(|e| anyhow::anyhow!("Failed to lock context: {}", e))?;
+    let ctx = ctx_manager.get_or_load(model_path, ignore_memory_check)?;
+
+    // Create state for this chunk
+    let mut state = ctx
+        .create_state()
+        .context("Failed to create Whisper state")?;
+
+    // Configure parameters for live transcription (FAST)
+    let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+
+    params.set_language(Some(language));
+    params.set_translate(translate);
+
+    // Use all CPU cores
+    let num_threads = num_cpus::get() as i32;
+    params.set_n_threads(num_threads);
+
+    // Silent mode (no console output)
+    params.set_print_progress(false);
+    params.set_print_special(false);
+    params.set_print_realtime(false);
+    params.set_print_timestamps(false);
+
+    // Speed optimizations
+    params.set_temperature(0.0); // Deterministic, faster
+    params.set_no_context(true); // Don't use past text as context
+
+    // Run transcription
+    state
+        .full(params, samples)
+        .context("Transcription failed")?;
+
+    // Collect segments
+    let num_segments = state.full_n_segments();
+    let mut segments = Vec::new();
+    let mut full_text = String::new();
+
+    for i in 0..num_segments {
+        if let Some(segment) = state.get_segment(i) {
+            let start = segment.start_timestamp() as f64 / 100.0;
+            let end = segment.end_timestamp() as f64 / 100.0;
+
+            if let Ok(text_cow) = segment.to_str_lossy() {
+                let text = text_cow.trim().to_string();
+                if !text.is_empty() {
+                    full_text.push_str(&text);
+                    full_text.push(' ');
+                    segments.push(TranscriptionSegment { start, end, text });
+                }
+            }
+        }
+    }
+
+    // Get detected language
+    let lang_id = state.full_lang_id_from_state();
+    let language = whisper_rs::get_lang_str(lang_id)
+        .unwrap_or("unknown")
+        .to_string();
+
+    Ok(LivePass {
+        text: full_text.trim().to_string(),
+        language,
+        segments,
+    })
+}
+
 /// Transcribe a live audio chunk (Stage 1: Chunked Processing)
 ///
 /// This function:
 /// 1. Converts WebM audio to WAV 16kHz mono
 /// 2. Loads/reuses Whisper context (tiny model)
 /// 3. Runs transcription with greedy sampling (best_of: 1 for speed)
-/// 4. Returns transcription result
+/// 4. Runs a second translate pass when `target_language` is set
+///    (skipped when the speech is already in the target language)
+/// 5. Returns transcription result
 pub fn transcribe_live_chunk(
     webm_data: &[u8],
     context_manager: &Arc<Mutex<LiveTranscriptionContext>>,
     model_path: &PathBuf,
+    target_language: Option<&str>,
+    ignore_memory_check: bool,
 ) -> Result<LiveTranscriptionResult> {
+    let translate = translation_requested(target_language, model_path)?;
+
     let temp_dir = std::env::temp_dir();
     let wav_path = temp_dir.join("live_chunk.wav");
 
@@ -157,82 +328,150 @@ pub fn transcribe_live_chunk(
         );
     }
 
-    println!(
-        "🎤 [LiveTranscription] Processing {:.2}s of audio",
-        duration_seconds
-    );
-
-    // Step 3: Get or load Whisper context
-    let mut ctx_manager = context_manager
-        .lock()
-        .map_err(|e| anyhow::anyhow!("Failed to lock context: {}", e))?;
-    let ctx = ctx_manager.get_or_load(model_path)?;
-
-    // Step 4: Create state for this chunk
-    let mut state = ctx.create_state().context("Failed to create Whisper state")?;
+    debug!("Processing {:.2}s of audio", duration_seconds);
+
+    // Step 3: Transcribe in the spoken language (auto-detected)
+    let original = run_live_pass(
+        context_manager,
+        model_path,
+        &samples_mono,
+        "auto",
+        false,
+        ignore_memory_check,
+    )?;
+
+    // Step 4: Translate with the detected language as source
+    let translated_text = if !translate || original.text.is_empty() {
+        None
+    } else if original.language == WHISPER_TRANSLATION_TARGET {
+        Some(original.text.clone())
+    } else {
+        let translated = run_live_pass(
+            context_manager,
+            model_path,
+            &samples_mono,
+            &original.language,
+            true,
+            ignore_memory_check,
+        )?;
+        Some(translated.text)
+    };
 
-    // Step 5: Configure parameters for live transcription (FAST)
-    let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+    // Clean up temp WAV file
+    let _ = std::fs::remove_file(&wav_path);
 
-    // Auto-detect language
-    params.set_language(Some("auto"));
+    info!(
+        "Transcribed {} segments (language: {}, translated: {})",
+        original.segments.len(),
+        original.language,
+        translated_text.is_some()
+    );
 
-    // Use all CPU cores
-    let num_threads = num_cpus::get() as i32;
-    params.set_n_threads(num_threads);
+    Ok(LiveTranscriptionResult {
+        text: original.text,
+        language: original.language,
+        segments: original.segments,
+        translated_text,
+    })
+}
 
-    // Silent mode (no console output)
-    params.set_print_progress(false);
-    params.set_print_special(false);
-    params.set_print_realtime(false);
-    params.set_print_timestamps(false);
+/// Resample the last 30s of an utterance to 16kHz (None if shorter than 0.5s,
+/// Whisper would mostly hallucinate)
+fn utterance_samples_16k(samples: &[i16], sample_rate: f32) -> Option<Vec<f32>> {
+    let max_samples = (MAX_UTTERANCE_SECONDS * sample_rate) as usize;
+    let samples = &samples[samples.len().saturating_sub(max_samples)..];
 
-    // Speed optimizations
-    params.set_temperature(0.0); // Deterministic, faster
-    params.set_no_context(true); // Don't use past text as context
+    let samples_16k = resample_to_16k(samples, sample_rate);
+    (samples_16k.len() >= 8_000).then_some(samples_16k)
+}
 
-    // Step 6: Run transcription
-    state.full(params, &samples_mono).context("Transcription failed")?;
+/// Re-transcribe a finished utterance captured by another recognizer (e.g. Vosk)
+/// `samples` are mono 16-bit PCM at `sample_rate`, only the last 30s are used
+pub fn transcribe_pcm_utterance(
+    samples: &[i16],
+    sample_rate: f32,
+    context_manager: &Arc<Mutex<LiveTranscriptionContext>>,
+    model_path: &PathBuf,
+    ignore_memory_check: bool,
+) -> Result<LiveTranscriptionResult> {
+    let Some(samples_16k) = utterance_samples_16k(samples, sample_rate) else {
+        anyhow::bail!("Utterance too short to be transcribed by Whisper");
+    };
 
-    // Step 7: Collect segments
-    let num_segments = state.full_n_segments();
-    let mut segments = Vec::new();
-    let mut full_text = String::new();
+    let pass = run_live_pass(
+        context_manager,
+        model_path,
+        &samples_16k,
+        "auto",
+        false,
+        ignore_memory_check,
+    )?;
 
-    for i in 0..num_segments {
-        if let Some(segment) = state.get_segment(i) {
-            let start = segment.start_timestamp() as f64 / 100.0;
-            let end = segment.end_timestamp() as f64 / 100.0;
+    Ok(LiveTranscriptionResult {
+        text: pass.text,
+        language: pass.language,
+        segments: pass.segments,
+        translated_text: None,
+    })
+}
 
-            if let Ok(text_cow) = segment.to_str_lossy() {
-                let text = text_cow.trim().to_string();
-                if !text.is_empty() {
-                    full_text.push_str(&text);
-                    full_text.push(' ');
-                    segments.push(TranscriptionSegment { start, end, text });
-                }
-            }
-        }
+/// Transcribe a complete recording (e.g. push-to-talk dictation)
+/// `samples` are mono 16-bit PCM at `sample_rate`, unlike utterances the whole
+/// recording is used (Whisper processes it in 30s windows)
+pub fn transcribe_pcm_recording(
+    samples: &[i16],
+    sample_rate: f32,
+    context_manager: &Arc<Mutex<LiveTranscriptionContext>>,
+    model_path: &PathBuf,
+    ignore_memory_check: bool,
+) -> Result<LiveTranscriptionResult> {
+    let samples_16k = resample_to_16k(samples, sample_rate);
+    if samples_16k.len() < 8_000 {
+        anyhow::bail!("Recording too short to be transcribed by Whisper");
     }
 
-    // Step 8: Get detected language
-    let lang_id = state.full_lang_id_from_state();
-    let language = whisper_rs::get_lang_str(lang_id)
-        .unwrap_or("unknown")
-        .to_string();
+    let pass = run_live_pass(
+        context_manager,
+        model_path,
+        &samples_16k,
+        "auto",
+        false,
+        ignore_memory_check,
+    )?;
 
-    // Clean up temp WAV file
-    let _ = std::fs::remove_file(&wav_path);
+    Ok(LiveTranscriptionResult {
+        text: pass.text,
+        language: pass.language,
+        segments: pass.segments,
+        translated_text: None,
+    })
+}
 
-    println!(
-        "✅ [LiveTranscription] Transcribed {} segments (language: {})",
-        segments.len(),
-        language
+/// Translate a finished utterance captured by another recognizer (e.g. Vosk)
+/// `samples` are mono 16-bit PCM at `sample_rate`, only the last 30s are used
+pub fn translate_pcm_utterance(
+    samples: &[i16],
+    sample_rate: f32,
+    context_manager: &Arc<Mutex<LiveTranscriptionContext>>,
+    model_path: &PathBuf,
+    ignore_memory_check: bool,
+) -> Result<String> {
+    let Some(samples_16k) = utterance_samples_16k(samples, sample_rate) else {
+        return Ok(String::new());
+    };
+
+    let translated = run_live_pass(
+        context_manager,
+        model_path,
+        &samples_16k,
+        "auto",
+        true,
+        ignore_memory_check,
+    )?;
+    debug!(
+        "Translated utterance from {}: {}",
+        translated.language, translated.text
     );
 
-    Ok(LiveTranscriptionResult {
-        text: full_text.trim().to_string(),
-        language,
-        segments,
-    })
+    Ok(translated.text)
 }
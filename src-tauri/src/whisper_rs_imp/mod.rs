@@ -1,2 +1,5 @@
-pub mod transcriber;
+pub mod benchmark;
+pub mod language_detector;
 pub mod live_transcriber;
+pub mod segment_filter;
+pub mod transcriber;
@@ -0,0 +1,145 @@
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use super::transcriber::TranscribedSegment;
+
+/// Hallucination filtering options (all disabled by default)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SegmentFilterSettings {
+    /// Drop segments whose average token probability is below this value (0.0 - 1.0)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_avg_probability: Option<f32>,
+    /// Drop segments whose no-speech probability is above this value (0.0 - 1.0)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_no_speech_probability: Option<f32>,
+    /// Drop segments repeating the previous kept segment word-for-word
+    #[serde(default)]
+    pub drop_repeated_segments: bool,
+}
+
+/// Normalize segment text for repetition checks ("Thanks for watching!" == "thanks for watching")
+fn normalize_text(text: &str) -> String {
+    text.chars()
+        .filter(|c| c.is_alphanumeric() || c.is_whitespace())
+        .collect::<String>()
+        .to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Drop low-confidence, silent and repeated segments according to `filter`.
+///
+/// Whisper tends to hallucinate stock phrases ("Thanks for watching!") over
+/// silence; those segments usually have a high no-speech probability, a low
+/// average token probability, or repeat the previous line verbatim.
+pub fn filter_segments(
+    segments: Vec<TranscribedSegment>,
+    filter: &SegmentFilterSettings,
+) -> Vec<TranscribedSegment> {
+    let total = segments.len();
+    let mut kept: Vec<TranscribedSegment> = Vec::with_capacity(total);
+
+    for segment in segments {
+        if let Some(min_probability) = filter.min_avg_probability {
+            if segment.avg_probability < min_probability {
+                debug!(
+                    "Dropped low-confidence segment ({:.2}): '{}'",
+                    segment.avg_probability, segment.text
+                );
+                continue;
+            }
+        }
+
+        if let Some(max_no_speech) = filter.max_no_speech_probability {
+            if segment.no_speech_probability > max_no_speech {
+                debug!(
+                    "Dropped no-speech segment ({:.2}): '{}'",
+                    segment.no_speech_probability, segment.text
+                );
+                continue;
+            }
+        }
+
+        if filter.drop_repeated_segments {
+            if let Some(previous) = kept.last() {
+                if normalize_text(&previous.text) == normalize_text(&segment.text) {
+                    debug!("Dropped repeated segment: '{}'", segment.text);
+                    continue;
+                }
+            }
+        }
+
+        kept.push(segment);
+    }
+
+    if kept.len() != total {
+        debug!("Kept {} of {} segments", kept.len(), total);
+    }
+
+    kept
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(text: &str, avg_probability: f32, no_speech_probability: f32) -> TranscribedSegment {
+        TranscribedSegment {
+            start: 0.0,
+            end: 1.0,
+            text: text.to_string(),
+            avg_probability,
+            no_speech_probability,
+            temperature: 0.0,
+        }
+    }
+
+    fn texts(segments: &[TranscribedSegment]) -> Vec<&str> {
+        segments.iter().map(|s| s.text.as_str()).collect()
+    }
+
+    #[test]
+    fn test_default_filter_keeps_everything() {
+        let segments = vec![segment("Hello", 0.1, 0.9), segment("Hello", 0.1, 0.9)];
+
+        let kept = filter_segments(segments, &SegmentFilterSettings::default());
+
+        assert_eq!(kept.len(), 2);
+    }
+
+    #[test]
+    fn test_filter_probability_thresholds() {
+        let segments = vec![
+            segment("Kept", 0.8, 0.1),
+            segment("Unsure", 0.3, 0.1),
+            segment("Thanks for watching!", 0.8, 0.9),
+        ];
+        let filter = SegmentFilterSettings {
+            min_avg_probability: Some(0.5),
+            max_no_speech_probability: Some(0.6),
+            drop_repeated_segments: false,
+        };
+
+        assert_eq!(texts(&filter_segments(segments, &filter)), ["Kept"]);
+    }
+
+    #[test]
+    fn test_filter_repeated_segments() {
+        let segments = vec![
+            segment("Thanks for watching!", 0.8, 0.1),
+            segment("thanks for  watching", 0.8, 0.1),
+            segment("Bye.", 0.8, 0.1),
+            segment("Thanks for watching!", 0.8, 0.1),
+        ];
+        let filter = SegmentFilterSettings {
+            drop_repeated_segments: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            texts(&filter_segments(segments, &filter)),
+            ["Thanks for watching!", "Bye.", "Thanks for watching!"]
+        );
+    }
+}
@@ -1,7 +1,17 @@
 use anyhow::{Context, Result};
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
 use serde::{Deserialize, Serialize};
+use std::io::Write;
 use std::path::Path;
-use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
+use tracing::debug;
+use whisper_rs::{
+    FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters, WhisperState,
+};
+
+use super::segment_filter::{filter_segments, SegmentFilterSettings};
+use crate::pipeline::audio::AudioPreprocessingSettings;
+use crate::pipeline::glossary::GlossarySettings;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SamplingStrategyConfig {
@@ -15,6 +25,31 @@ pub struct SamplingStrategyConfig {
     pub patience: Option<f32>,
 }
 
+/// Re-decode segments that look wrong at increasing temperatures
+/// (the fallback ladder of OpenAI's Whisper / whisper.cpp)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TemperatureFallbackSettings {
+    /// Temperatures tried in order for failing segments
+    /// (the first pass uses `TranscriptionSettings::temperature`)
+    pub schedule: Vec<f32>,
+    /// Retry when the text compresses better than this (zlib ratio, loops compress well)
+    pub compression_ratio_threshold: Option<f32>,
+    /// Retry when the average log-probability of the text tokens is below this
+    pub logprob_threshold: Option<f32>,
+}
+
+impl Default for TemperatureFallbackSettings {
+    /// Same thresholds as the reference implementation
+    fn default() -> Self {
+        Self {
+            schedule: vec![0.2, 0.4, 0.6, 0.8, 1.0],
+            compression_ratio_threshold: Some(2.4),
+            logprob_threshold: Some(-1.0),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TranscriptionSettings {
     pub preset: String,
@@ -29,29 +64,69 @@ pub struct TranscriptionSettings {
     pub entropy_threshold: Option<f32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub no_speech_threshold: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filtering: Option<SegmentFilterSettings>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preprocessing: Option<AudioPreprocessingSettings>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub glossary: Option<GlossarySettings>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature_fallback: Option<TemperatureFallbackSettings>,
+    /// Spoken language code (e.g. "fr"), skips language detection when set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
 }
 
-/// Transcribe a single WAV audio file using whisper_rs.
-///
-/// Requirements:
-/// - WAV must be 16kHz, 16-bit PCM.
-/// - Automatically converts stereo to mono if needed.
-/// - Model must be a `ggml-*.bin` file.
-///
-/// Parameters:
-/// - `auto_detect_language`: If true, uses "auto" for language detection. If false, uses "en".
-/// - `settings`: Optional transcription settings (sampling strategy, temperature, etc.)
-///
-/// Returns: (language, segments) where segments = Vec<(start_time, end_time, text)>
+impl Default for TranscriptionSettings {
+    /// "balanced" preset (greedy, best_of 5, temperature 0)
+    fn default() -> Self {
+        TranscriptionSettings {
+            preset: "balanced".to_string(),
+            sampling_strategy: SamplingStrategyConfig {
+                strategy_type: "greedy".to_string(),
+                best_of: Some(5),
+                beam_size: None,
+                patience: None,
+            },
+            temperature: 0.0,
+            thread_count: Some("auto".to_string()),
+            no_context: true,
+            initial_prompt: None,
+            max_text_context: None,
+            entropy_threshold: None,
+            no_speech_threshold: None,
+            filtering: None,
+            preprocessing: None,
+            glossary: None,
+            temperature_fallback: None,
+            language: None,
+        }
+    }
+}
+
+/// A single transcribed segment with Whisper's confidence metrics
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscribedSegment {
+    pub start: f64, // seconds
+    pub end: f64,   // seconds
+    pub text: String,
+    pub avg_probability: f32, // mean probability of the text tokens (0.0 - 1.0)
+    pub no_speech_probability: f32, // probability the segment is silence (0.0 - 1.0)
+    /// Temperature the segment was decoded at (higher = temperature fallback retries)
+    pub temperature: f32,
+}
+
+/// True if Whisper knows the language code (e.g. "fr", full names like "french" aren't accepted)
+pub fn is_supported_language(code: &str) -> bool {
+    !code.is_empty()
+        && code.chars().all(|c| c.is_ascii_lowercase())
+        && whisper_rs::get_lang_id(code).and_then(whisper_rs::get_lang_str) == Some(code)
+}
+
+/// Load a 16kHz 16-bit PCM WAV file as mono f32 samples ready for Whisper.
 ///
-/// This function follows the whisper_rs example closely for maximum CPU efficiency.
-pub fn transcribe_single_pass(
-    model_path: &Path,
-    wav_path: &Path,
-    auto_detect_language: bool,
-    settings: Option<TranscriptionSettings>,
-) -> Result<(String, Vec<(f64, f64, String)>)> {
-    // --- 1️⃣ Load audio ---
+/// Automatically converts stereo to mono if needed.
+pub fn load_wav_samples(wav_path: &Path) -> Result<Vec<f32>> {
     let mut reader = hound::WavReader::open(wav_path).context("Failed to open WAV file")?;
     let spec = reader.spec();
 
@@ -92,44 +167,44 @@ pub fn transcribe_single_pass(
         );
     };
 
-    // --- 2️⃣ Load Whisper model ---
-    let ctx = WhisperContext::new_with_params(
-        model_path.to_str().context("Invalid model path")?,
-        WhisperContextParameters::default(),
-    )
-    .context("Failed to load Whisper model")?;
+    Ok(samples_mono)
+}
 
-    // --- 3️⃣ Create state (once) ---
-    let mut state = ctx
-        .create_state()
-        .context("Failed to create Whisper state")?;
+/// Whisper works on 16kHz audio
+const SAMPLE_RATE: f64 = 16_000.0;
 
-    // --- 4️⃣ Configure decoding ---
-    // Create default settings if none provided
-    let default_settings = TranscriptionSettings {
-        preset: "balanced".to_string(),
-        sampling_strategy: SamplingStrategyConfig {
-            strategy_type: "greedy".to_string(),
-            best_of: Some(5),
-            beam_size: None,
-            patience: None,
-        },
-        temperature: 0.0,
-        thread_count: Some("auto".to_string()),
-        no_context: true,
-        initial_prompt: None,
-        max_text_context: None,
-        entropy_threshold: None,
-        no_speech_threshold: None,
-    };
-    let config = settings.unwrap_or(default_settings);
+/// Re-decoded windows shorter than this are left alone (seconds)
+const MIN_FALLBACK_WINDOW_SECONDS: f64 = 0.3;
+
+/// Consecutive segments are checked together over up to one Whisper window
+/// (seconds), so a phrase repeated across short segments is still caught
+const MAX_FALLBACK_WINDOW_SECONDS: f64 = 30.0;
 
+/// Segments above this no-speech probability that fail the log-prob check are
+/// silence, not decoding failures (used when `no_speech_threshold` isn't set)
+const DEFAULT_NO_SPEECH_THRESHOLD: f32 = 0.6;
+
+/// A decoded segment with the metrics used by the fallback ladder
+struct DecodedSegment {
+    segment: TranscribedSegment,
+    avg_logprob: f32,
+}
+
+/// Decoding parameters for one pass at `temperature`
+fn build_params<'a>(
+    config: &TranscriptionSettings,
+    language_code: &'a str,
+    temperature: f32,
+) -> FullParams<'a, 'a> {
     // Apply sampling strategy
     let mut params = match config.sampling_strategy.strategy_type.as_str() {
         "beam_search" => {
             let beam_size = config.sampling_strategy.beam_size.unwrap_or(5);
             let patience = config.sampling_strategy.patience.unwrap_or(-1.0);
-            println!("🔍 [Whisper] Using BeamSearch strategy with beam_size: {}, patience: {}", beam_size, patience);
+            debug!(
+                "Using BeamSearch strategy with beam_size: {}, patience: {}",
+                beam_size, patience
+            );
             FullParams::new(SamplingStrategy::BeamSearch {
                 beam_size,
                 patience,
@@ -137,13 +212,11 @@ pub fn transcribe_single_pass(
         }
         _ => {
             let best_of = config.sampling_strategy.best_of.unwrap_or(5);
-            println!("🔍 [Whisper] Using Greedy strategy with best_of: {}", best_of);
+            debug!("Using Greedy strategy with best_of: {}", best_of);
             FullParams::new(SamplingStrategy::Greedy { best_of })
         }
     };
 
-    // Set language: "auto" for detection or "en" for English
-    let language_code = if auto_detect_language { "auto" } else { "en" };
     params.set_language(Some(language_code));
 
     // Performance: Use all available CPU cores for faster transcription
@@ -158,44 +231,310 @@ pub fn transcribe_single_pass(
     params.set_print_timestamps(false);
 
     // Apply user-configurable settings
-    println!("🔍 [Whisper] Temperature: {}", config.temperature);
-    println!("🔍 [Whisper] No Context: {}", config.no_context);
-    params.set_temperature(config.temperature);
+    debug!("Temperature: {}", temperature);
+    debug!("No Context: {}", config.no_context);
+    params.set_temperature(temperature);
     params.set_no_context(config.no_context);
 
+    // The ladder below retries per segment and reports the temperature used,
+    // so whisper.cpp's own (unreported) fallback is turned off
+    if config.temperature_fallback.is_some() {
+        params.set_temperature_inc(0.0);
+    }
+
     // Set initial prompt if provided
     if let Some(prompt) = &config.initial_prompt {
         if !prompt.is_empty() {
-            println!("🔍 [Whisper] Initial Prompt: '{}'", prompt);
+            debug!("Initial Prompt: '{}'", prompt);
             params.set_initial_prompt(prompt);
         }
     }
 
-    // --- 5️⃣ Run transcription ---
-    state
-        .full(params, &samples_mono)
-        .context("Transcription failed")?;
+    params
+}
 
-    // --- 6️⃣ Collect results ---
+/// Collect the segments of the last pass (`offset` shifts the timestamps)
+fn collect_segments(
+    state: &WhisperState,
+    ctx: &WhisperContext,
+    temperature: f32,
+    offset: f64,
+) -> Vec<DecodedSegment> {
     let num_segments = state.full_n_segments();
     let mut segments = Vec::new();
 
     for i in 0..num_segments {
         if let Some(segment) = state.get_segment(i) {
-            let start = segment.start_timestamp() as f64 / 100.0; // Convert to seconds
-            let end = segment.end_timestamp() as f64 / 100.0;
+            let start = segment.start_timestamp() as f64 / 100.0 + offset; // Convert to seconds
+            let end = segment.end_timestamp() as f64 / 100.0 + offset;
 
             if let Ok(text_cow) = segment.to_str_lossy() {
                 let text = text_cow.trim().to_string();
                 if !text.is_empty() {
-                    segments.push((start, end, text));
+                    // Average over text tokens only (special tokens like [_BEG_] skew the score)
+                    let text_token_probabilities: Vec<f32> = (0..segment.n_tokens())
+                        .filter_map(|t| segment.get_token(t))
+                        .filter(|token| token.token_id() < ctx.token_eot())
+                        .map(|token| token.token_probability())
+                        .collect();
+                    let (avg_probability, avg_logprob) = if text_token_probabilities.is_empty() {
+                        (0.0, f32::NEG_INFINITY)
+                    } else {
+                        let count = text_token_probabilities.len() as f32;
+                        (
+                            text_token_probabilities.iter().sum::<f32>() / count,
+                            text_token_probabilities
+                                .iter()
+                                .map(|p| p.max(f32::MIN_POSITIVE).ln())
+                                .sum::<f32>()
+                                / count,
+                        )
+                    };
+
+                    segments.push(DecodedSegment {
+                        segment: TranscribedSegment {
+                            start,
+                            end,
+                            text,
+                            avg_probability,
+                            no_speech_probability: segment.no_speech_probability(),
+                            temperature,
+                        },
+                        avg_logprob,
+                    });
                 }
             }
         }
     }
 
-    // --- 7️⃣ Get detected language ---
-    let detected_language = if auto_detect_language {
+    segments
+}
+
+/// zlib compression ratio of the text (repetition loops compress very well)
+fn compression_ratio(text: &str) -> f32 {
+    if text.is_empty() {
+        return 0.0;
+    }
+
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    let compressed_len = encoder
+        .write_all(text.as_bytes())
+        .and_then(|_| encoder.finish())
+        .map(|compressed| compressed.len())
+        .unwrap_or(text.len());
+
+    text.len() as f32 / compressed_len.max(1) as f32
+}
+
+/// True if the segments fail the fallback thresholds and should be re-decoded
+fn needs_fallback(
+    segments: &[DecodedSegment],
+    fallback: &TemperatureFallbackSettings,
+    no_speech_threshold: f32,
+) -> bool {
+    if segments.is_empty() {
+        return false;
+    }
+
+    let text = segments
+        .iter()
+        .map(|s| s.segment.text.as_str())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let avg_logprob = average_logprob(segments);
+
+    let too_repetitive = fallback
+        .compression_ratio_threshold
+        .is_some_and(|threshold| compression_ratio(&text) > threshold);
+    let too_unlikely = fallback
+        .logprob_threshold
+        .is_some_and(|threshold| avg_logprob < threshold);
+
+    // Low confidence on silence is expected, retrying would only invent text
+    let is_silence = too_unlikely
+        && segments
+            .iter()
+            .all(|s| s.segment.no_speech_probability > no_speech_threshold);
+
+    (too_repetitive || too_unlikely) && !is_silence
+}
+
+fn average_logprob(segments: &[DecodedSegment]) -> f32 {
+    if segments.is_empty() {
+        return f32::NEG_INFINITY;
+    }
+    segments.iter().map(|s| s.avg_logprob).sum::<f32>() / segments.len() as f32
+}
+
+/// Group consecutive segments into windows of up to `MAX_FALLBACK_WINDOW_SECONDS`
+/// (a longer segment gets a window of its own)
+fn fallback_windows(segments: Vec<DecodedSegment>) -> Vec<Vec<DecodedSegment>> {
+    let mut windows: Vec<Vec<DecodedSegment>> = Vec::new();
+
+    for decoded in segments {
+        let fits_last_window = windows.last().is_some_and(|window| {
+            decoded.segment.end - window[0].segment.start <= MAX_FALLBACK_WINDOW_SECONDS
+        });
+
+        match windows.last_mut() {
+            Some(window) if fits_last_window => window.push(decoded),
+            _ => windows.push(vec![decoded]),
+        }
+    }
+
+    windows
+}
+
+/// Re-decode the audio of each window of segments failing the thresholds
+/// (checked on the window's whole text, like the reference implementation)
+/// with the next temperatures of the schedule, keeping the first attempt that
+/// passes (or the most likely attempt when none does)
+fn apply_temperature_fallback(
+    state: &mut WhisperState,
+    ctx: &WhisperContext,
+    config: &TranscriptionSettings,
+    fallback: &TemperatureFallbackSettings,
+    language_code: &str,
+    samples: &[f32],
+    segments: Vec<DecodedSegment>,
+) -> Result<Vec<DecodedSegment>> {
+    let no_speech_threshold = config
+        .no_speech_threshold
+        .unwrap_or(DEFAULT_NO_SPEECH_THRESHOLD);
+    let schedule: Vec<f32> = fallback
+        .schedule
+        .iter()
+        .copied()
+        .filter(|t| *t > config.temperature)
+        .collect();
+
+    let mut output = Vec::with_capacity(segments.len());
+
+    for window_segments in fallback_windows(segments) {
+        let window = (
+            window_segments[0].segment.start,
+            window_segments[window_segments.len() - 1].segment.end,
+        );
+        if schedule.is_empty()
+            || window.1 - window.0 < MIN_FALLBACK_WINDOW_SECONDS
+            || !needs_fallback(&window_segments, fallback, no_speech_threshold)
+        {
+            output.extend(window_segments);
+            continue;
+        }
+
+        let first_sample = ((window.0 * SAMPLE_RATE) as usize).min(samples.len());
+        let last_sample = ((window.1 * SAMPLE_RATE).ceil() as usize).min(samples.len());
+        let window_samples = &samples[first_sample..last_sample];
+
+        let mut best = window_segments;
+        let mut best_logprob = average_logprob(&best);
+
+        for &temperature in &schedule {
+            debug!(
+                "Retrying {:.2}s - {:.2}s at temperature {}",
+                window.0, window.1, temperature
+            );
+
+            let params = build_params(config, language_code, temperature);
+            state
+                .full(params, window_samples)
+                .context("Fallback decoding failed")?;
+
+            // Keep the window's boundaries, Whisper may place text slightly outside
+            let attempt: Vec<DecodedSegment> = collect_segments(state, ctx, temperature, window.0)
+                .into_iter()
+                .map(|mut decoded| {
+                    decoded.segment.start = decoded.segment.start.clamp(window.0, window.1);
+                    decoded.segment.end = decoded.segment.end.clamp(window.0, window.1);
+                    decoded
+                })
+                .collect();
+
+            if attempt.is_empty() {
+                continue;
+            }
+            if !needs_fallback(&attempt, fallback, no_speech_threshold) {
+                best = attempt;
+                break;
+            }
+
+            let attempt_logprob = average_logprob(&attempt);
+            if attempt_logprob > best_logprob {
+                best_logprob = attempt_logprob;
+                best = attempt;
+            }
+        }
+
+        output.extend(best);
+    }
+
+    Ok(output)
+}
+
+/// Transcribe a single WAV audio file using whisper_rs.
+///
+/// Requirements:
+/// - WAV must be 16kHz, 16-bit PCM.
+/// - Automatically converts stereo to mono if needed.
+/// - Model must be a `ggml-*.bin` file.
+///
+/// Parameters:
+/// - `auto_detect_language`: If true, uses "auto" for language detection. If false, uses "en".
+///   Ignored when `settings.language` is set.
+/// - `settings`: Optional transcription settings (sampling strategy, temperature, etc.)
+///
+/// With `settings.temperature_fallback`, windows of segments failing the
+/// compression-ratio or log-prob thresholds are re-decoded at the next
+/// temperatures of the schedule.
+///
+/// Returns: (language, segments) with per-segment confidence metrics and the
+/// temperature each segment was decoded at, after applying `settings.filtering` (if any).
+///
+/// This function follows the whisper_rs example closely for maximum CPU efficiency.
+pub fn transcribe_single_pass(
+    model_path: &Path,
+    wav_path: &Path,
+    auto_detect_language: bool,
+    settings: Option<TranscriptionSettings>,
+) -> Result<(String, Vec<TranscribedSegment>)> {
+    // --- 1️⃣ Load audio ---
+    let samples_mono = load_wav_samples(wav_path)?;
+
+    // --- 2️⃣ Load Whisper model ---
+    let ctx = WhisperContext::new_with_params(
+        model_path.to_str().context("Invalid model path")?,
+        WhisperContextParameters::default(),
+    )
+    .context("Failed to load Whisper model")?;
+
+    // --- 3️⃣ Create state (once) ---
+    let mut state = ctx
+        .create_state()
+        .context("Failed to create Whisper state")?;
+
+    // --- 4️⃣ Configure decoding ---
+    // Use default settings if none provided
+    let config = settings.unwrap_or_default();
+
+    // Set language: the requested code, "auto" for detection or "en" for English
+    let language_code = match config.language.as_deref() {
+        Some(code) if !is_supported_language(code) => {
+            anyhow::bail!("Unsupported language code '{}'", code)
+        }
+        Some(code) => code,
+        None if auto_detect_language => "auto",
+        None => "en",
+    };
+    let params = build_params(&config, language_code, config.temperature);
+
+    // --- 5️⃣ Run transcription ---
+    state
+        .full(params, &samples_mono)
+        .context("Transcription failed")?;
+
+    // --- 6️⃣ Get detected language (before fallback passes overwrite the state) ---
+    let detected_language = if language_code == "auto" {
         // Retrieve the detected language ID from the state
         let lang_id = state.full_lang_id_from_state();
         // Convert language ID to language code (e.g., "en", "fr", "es")
@@ -206,5 +545,119 @@ pub fn transcribe_single_pass(
         language_code.to_string()
     };
 
+    // --- 7️⃣ Collect results (+ temperature fallback) ---
+    let mut decoded = collect_segments(&state, &ctx, config.temperature, 0.0);
+
+    if let Some(fallback) = &config.temperature_fallback {
+        // Retries use the detected language, a short window could be misdetected
+        let retry_language = if detected_language == "unknown" {
+            language_code
+        } else {
+            detected_language.as_str()
+        };
+        decoded = apply_temperature_fallback(
+            &mut state,
+            &ctx,
+            &config,
+            fallback,
+            retry_language,
+            &samples_mono,
+            decoded,
+        )?;
+    }
+
+    let mut segments: Vec<TranscribedSegment> =
+        decoded.into_iter().map(|decoded| decoded.segment).collect();
+
+    // Drop hallucinated segments if requested
+    if let Some(filter) = &config.filtering {
+        segments = filter_segments(segments, filter);
+    }
+
     Ok((detected_language, segments))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decoded(start: f64, end: f64, text: &str, avg_logprob: f32) -> DecodedSegment {
+        DecodedSegment {
+            segment: TranscribedSegment {
+                start,
+                end,
+                text: text.to_string(),
+                avg_probability: 0.9,
+                no_speech_probability: 0.1,
+                temperature: 0.0,
+            },
+            avg_logprob,
+        }
+    }
+
+    #[test]
+    fn test_compression_ratio() {
+        assert_eq!(compression_ratio(""), 0.0);
+
+        let sentence = "The quick brown fox jumps over the lazy dog near the river bank.";
+        assert!(compression_ratio(sentence) < 2.4);
+        assert!(compression_ratio(&"I'm sorry. ".repeat(20)) > 2.4);
+    }
+
+    #[test]
+    fn test_repetition_across_segments_needs_fallback() {
+        let fallback = TemperatureFallbackSettings::default();
+        let segments: Vec<DecodedSegment> = (0..12)
+            .map(|i| decoded(i as f64, i as f64 + 1.0, "Thank you.", -0.2))
+            .collect();
+
+        // Each short segment looks fine on its own, the loop only shows together
+        for segment in &segments {
+            assert!(!needs_fallback(
+                std::slice::from_ref(segment),
+                &fallback,
+                DEFAULT_NO_SPEECH_THRESHOLD
+            ));
+        }
+        assert!(needs_fallback(
+            &segments,
+            &fallback,
+            DEFAULT_NO_SPEECH_THRESHOLD
+        ));
+    }
+
+    #[test]
+    fn test_low_logprob_needs_fallback_unless_silence() {
+        let fallback = TemperatureFallbackSettings::default();
+        let mut segments = vec![decoded(0.0, 2.0, "Hello there.", -1.5)];
+
+        assert!(needs_fallback(
+            &segments,
+            &fallback,
+            DEFAULT_NO_SPEECH_THRESHOLD
+        ));
+
+        segments[0].segment.no_speech_probability = 0.9;
+        assert!(!needs_fallback(
+            &segments,
+            &fallback,
+            DEFAULT_NO_SPEECH_THRESHOLD
+        ));
+        assert!(!needs_fallback(&[], &fallback, DEFAULT_NO_SPEECH_THRESHOLD));
+    }
+
+    #[test]
+    fn test_fallback_windows() {
+        let segments = vec![
+            decoded(0.0, 10.0, "One", -0.2),
+            decoded(10.0, 25.0, "Two", -0.2),
+            decoded(25.0, 31.0, "Three", -0.2),
+            decoded(31.0, 75.0, "Four", -0.2),
+            decoded(75.0, 76.0, "Five", -0.2),
+        ];
+
+        let window_sizes: Vec<usize> = fallback_windows(segments).iter().map(Vec::len).collect();
+
+        assert_eq!(window_sizes, [2, 1, 1, 1]);
+    }
+}
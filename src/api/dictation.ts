@@ -0,0 +1,67 @@
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import { invokeCommand } from "./client";
+
+export type DictationMode = "push_to_talk" | "toggle";
+export type DictationEngine = "whisper" | "vosk"; // vosk: Windows/Linux only
+export type DictationOutput = "clipboard" | "type";
+export type DictationPhase = "disabled" | "idle" | "recording" | "transcribing";
+
+export type DictationSettings = {
+  shortcut?: string; // defaults to "CommandOrControl+Shift+Space"
+  mode?: DictationMode; // defaults to "push_to_talk" (record while held)
+  engine?: DictationEngine; // defaults to "whisper"
+  model_name?: string; // Whisper model name or Vosk model folder (defaults to "base")
+  output?: DictationOutput; // defaults to "clipboard", "type" types into the focused app
+  play_sounds?: boolean; // start/stop cues (defaults to true)
+  ignore_memory_check?: boolean; // load the Whisper model even if it likely doesn't fit in memory
+};
+
+export type DictationStatus = {
+  phase: DictationPhase;
+  settings: Required<DictationSettings> | null; // null when disabled
+};
+
+export type DictationResult = {
+  text: string; // empty when nothing was said
+  error?: string;
+};
+
+/**
+ * Enable system-wide dictation on a global shortcut (handled by the backend,
+ * works while the app is in the background)
+ * @param settings - Shortcut, engine and output, null to disable dictation
+ */
+export async function setDictationSettings(
+  settings: DictationSettings | null
+): Promise<DictationStatus> {
+  return invokeCommand<DictationStatus>("set_dictation_settings", {
+    settings,
+  });
+}
+
+export async function getDictationStatus(): Promise<DictationStatus> {
+  return invokeCommand<DictationStatus>("get_dictation_status");
+}
+
+/**
+ * Listen to dictation state changes, partial text (Vosk only) and results
+ */
+export async function listenToDictation(handlers: {
+  onStatus?: (status: DictationStatus) => void;
+  onPartial?: (text: string) => void;
+  onResult?: (result: DictationResult) => void;
+}): Promise<UnlistenFn> {
+  const unlisteners = await Promise.all([
+    listen<DictationStatus>("dictation-status", (event) =>
+      handlers.onStatus?.(event.payload)
+    ),
+    listen<string>("dictation-partial", (event) =>
+      handlers.onPartial?.(event.payload)
+    ),
+    listen<DictationResult>("dictation-result", (event) =>
+      handlers.onResult?.(event.payload)
+    ),
+  ]);
+
+  return () => unlisteners.forEach((unlisten) => unlisten());
+}
@@ -2,6 +2,7 @@ import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
 import { z } from "zod";
 import type { TranscriptionSettings } from "@app/types/transcriptionSettings";
+import { jobProgressEvent } from "../jobs";
 
 // Request schema
 export const TranscribeRequestSchema = z.object({
@@ -28,17 +29,55 @@ export const TranscribeAdvancedResponseSchema = z.object({
       start_time: z.number(),
       end_time: z.number(),
       text: z.string(),
+      avg_probability: z.number().nullable().optional(),
+      no_speech_probability: z.number().nullable().optional(),
+      temperature: z.number().nullable().optional(), // Higher than the setting when the fallback retried it
     })
   ),
+  result_id: z.string().optional(), // saved result, for the segment editing commands
 });
 
 export type TranscribeAdvancedResponse = z.infer<
   typeof TranscribeAdvancedResponseSchema
 >;
 
+// Range re-transcription response schema
+export const TranscribeRangeResponseSchema = z.object({
+  language: z.string(),
+  start_sec: z.number(),
+  end_sec: z.number(),
+  segments: TranscribeAdvancedResponseSchema.shape.segments,
+});
+
+export type TranscribeRangeResponse = z.infer<
+  typeof TranscribeRangeResponseSchema
+>;
+
+// Language detection response schema
+export const LanguageDetectionResponseSchema = z.object({
+  language: z.string(),
+  candidates: z.array(
+    z.object({
+      code: z.string(),
+      name: z.string(),
+      probability: z.number(),
+    })
+  ),
+});
+
+export type LanguageDetectionResponse = z.infer<
+  typeof LanguageDetectionResponseSchema
+>;
+
 // Progress event types
 export type TranscriptionProgress =
   | { type: "converting"; message: string }
+  | {
+      type: "preprocessed";
+      filters: string; // ffmpeg filter chain that was applied
+      rms_before_db: number | null; // Input level (dBFS)
+      rms_after_db: number | null; // Level after preprocessing (dBFS)
+    }
   | { type: "detecting_language" }
   | { type: "language_detected"; language: string }
   | { type: "transcribing"; progress: number }
@@ -91,6 +130,8 @@ export async function transcribeFile(
  * @param detectLanguage - Auto-detect language (defaults to true)
  * @param settings - Transcription settings (sampling strategy, temperature, etc.)
  * @param onProgress - Callback for progress updates
+ * @param jobId - Job id (generated when omitted), see `listJobs`/`getJobStatus`
+ * @param ignoreMemoryCheck - Load the model even if it likely doesn't fit in memory
  * @returns Full transcription result with subtitles and metadata
  */
 export async function transcribeFileAdvanced(
@@ -98,7 +139,9 @@ export async function transcribeFileAdvanced(
   modelName?: string,
   detectLanguage: boolean = true,
   settings?: TranscriptionSettings,
-  onProgress?: ProgressCallback
+  onProgress?: ProgressCallback,
+  jobId: string = crypto.randomUUID(),
+  ignoreMemoryCheck: boolean = false
 ): Promise<TranscribeAdvancedResponse> {
   // Validate request
   const request = TranscribeRequestSchema.parse({
@@ -106,12 +149,12 @@ export async function transcribeFileAdvanced(
     modelName,
   });
 
-  // Setup progress listener
+  // Setup progress listener (before invoking, so no event is missed)
   let unlisten: (() => void) | undefined;
 
   if (onProgress) {
     unlisten = await listen<TranscriptionProgress>(
-      "transcription-progress",
+      jobProgressEvent(jobId),
       (event) => {
         onProgress(event.payload);
       }
@@ -127,6 +170,8 @@ export async function transcribeFileAdvanced(
         modelName: request.modelName,
         detectLanguage,
         settings: settings || null,
+        jobId,
+        ignoreMemoryCheck,
       }
     );
 
@@ -148,3 +193,135 @@ export async function transcribeFileAdvanced(
     }
   }
 }
+
+/**
+ * Detect the spoken language of a file (first ~30 seconds only)
+ * @param filePath - Absolute path to the audio/video file
+ * @param modelName - Multilingual Whisper model name (e.g. "base", not "base.en")
+ * @param ignoreMemoryCheck - Load the model even if it likely doesn't fit in memory
+ * @returns Detected language and top candidates with probabilities
+ */
+export async function detectLanguage(
+  filePath: string,
+  modelName: string,
+  ignoreMemoryCheck: boolean = false
+): Promise<LanguageDetectionResponse> {
+  const request = TranscribeRequestSchema.parse({
+    filePath,
+    modelName,
+  });
+
+  try {
+    const result = await invoke<LanguageDetectionResponse>("detect_language", {
+      filePath: request.filePath,
+      modelName: request.modelName,
+      ignoreMemoryCheck,
+    });
+
+    return LanguageDetectionResponseSchema.parse(result);
+  } catch (error) {
+    if (typeof error === "string") {
+      throw new Error(error);
+    }
+    if (error instanceof Error) {
+      throw error;
+    }
+    throw new Error("Unknown error occurred during language detection");
+  }
+}
+
+/**
+ * Re-transcribe a time range of a file (e.g. a garbled section with different settings)
+ * @param filePath - Absolute path to the audio/video file
+ * @param startSec - Range start in seconds (original timeline)
+ * @param endSec - Range end in seconds (original timeline)
+ * @param modelName - Whisper model name (defaults to "base" in Rust)
+ * @param detectLanguage - Auto-detect language (defaults to true)
+ * @param settings - Transcription settings for this range
+ * @param ignoreMemoryCheck - Load the model even if it likely doesn't fit in memory
+ * @returns Segments with timestamps on the original timeline, ready to splice in
+ */
+export async function transcribeRange(
+  filePath: string,
+  startSec: number,
+  endSec: number,
+  modelName?: string,
+  detectLanguage: boolean = true,
+  settings?: TranscriptionSettings,
+  ignoreMemoryCheck: boolean = false
+): Promise<TranscribeRangeResponse> {
+  const request = TranscribeRequestSchema.parse({
+    filePath,
+    modelName,
+  });
+
+  try {
+    const result = await invoke<TranscribeRangeResponse>("transcribe_range", {
+      filePath: request.filePath,
+      startSec,
+      endSec,
+      modelName: request.modelName,
+      options: {
+        auto_detect_language: detectLanguage,
+        settings: settings || null,
+        ignore_memory_check: ignoreMemoryCheck,
+      },
+    });
+
+    return TranscribeRangeResponseSchema.parse(result);
+  } catch (error) {
+    if (typeof error === "string") {
+      throw new Error(error);
+    }
+    if (error instanceof Error) {
+      throw error;
+    }
+    throw new Error("Unknown error occurred during range transcription");
+  }
+}
+
+// Subtitle timing adjustment response schema
+export const AdjustedSubtitlesResponseSchema = z.object({
+  segments: TranscribeAdvancedResponseSchema.shape.segments,
+  subtitles_srt: z.string(),
+  subtitles_vtt: z.string(),
+  subtitles_ass: z.string(),
+});
+
+export type AdjustedSubtitlesResponse = z.infer<
+  typeof AdjustedSubtitlesResponseSchema
+>;
+
+/**
+ * Shift/rescale subtitle timestamps without re-transcribing (t' = t * scale + offset)
+ * @param segments - Segments to adjust (e.g. from a previous transcription)
+ * @param offsetSeconds - Constant shift in seconds, negative = earlier (defaults to 0)
+ * @param scale - Linear rescale factor to fix drift (defaults to 1)
+ * @param frameRate - Snap timestamps to frame boundaries of this frame rate
+ * @returns Adjusted segments and regenerated SRT/VTT/ASS subtitles
+ */
+export async function adjustSubtitles(
+  segments: TranscribeAdvancedResponse["segments"],
+  offsetSeconds?: number,
+  scale?: number,
+  frameRate?: number
+): Promise<AdjustedSubtitlesResponse> {
+  try {
+    const result = await invoke<AdjustedSubtitlesResponse>("adjust_subtitles", {
+      segments,
+      offsetSeconds,
+      scale,
+      frameRate,
+    });
+
+    return AdjustedSubtitlesResponseSchema.parse(result);
+  } catch (error) {
+    if (typeof error === "string") {
+      throw new Error(error);
+    }
+    if (error instanceof Error) {
+      throw error;
+    }
+    throw new Error("Unknown error occurred while adjusting subtitles");
+  }
+}
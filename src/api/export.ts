@@ -0,0 +1,47 @@
+import { invokeCommand } from "./client";
+
+export type DocumentFormat = "markdown" | "html";
+
+// Subtitle segments can be passed as-is (extra fields are ignored)
+export type TranscriptSegment = {
+  start_time: number;
+  end_time: number;
+  text: string;
+  speaker?: string; // e.g. "Alice" or "Speaker 1"
+};
+
+export type TranscriptDocumentOptions = {
+  format?: DocumentFormat; // defaults to "markdown"
+  title?: string;
+  paragraph_pause_seconds?: number; // pause starting a new paragraph (defaults to 2)
+  chapter_silence_seconds?: number | null; // silence starting a new chapter (defaults to 10, null = disabled)
+  chapter_keywords?: string[]; // e.g. ["next topic", "moving on"]
+  include_timestamps?: boolean; // defaults to true
+};
+
+export type TranscriptChapter = {
+  title: string;
+  start_time: number;
+};
+
+export type TranscriptDocument = {
+  format: DocumentFormat;
+  content: string;
+  chapters: TranscriptChapter[]; // empty when no chapter boundary was found
+  paragraph_count: number;
+};
+
+/**
+ * Build a Markdown/HTML transcript for meeting notes
+ * @param segments - Transcript segments (with optional speaker labels)
+ * @param options - Paragraph/chapter grouping and output format
+ */
+export async function generateTranscriptDocument(
+  segments: TranscriptSegment[],
+  options?: TranscriptDocumentOptions
+): Promise<TranscriptDocument> {
+  return invokeCommand<TranscriptDocument>("generate_transcript_document", {
+    segments,
+    options: options ?? null,
+  });
+}
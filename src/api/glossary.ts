@@ -0,0 +1,38 @@
+import { invokeCommand } from "./client";
+
+export type GlossaryReplacement = {
+  find: string; // e.g. "cube ernie tees"
+  replace: string; // e.g. "Kubernetes"
+  case_sensitive?: boolean; // defaults to false
+};
+
+export type Glossary = {
+  name: string;
+  terms: string[]; // composed into Whisper's initial prompt
+  replacements: GlossaryReplacement[]; // applied to the output when enabled
+};
+
+/**
+ * List saved glossaries
+ */
+export async function listGlossaries(): Promise<Glossary[]> {
+  return invokeCommand<Glossary[]>("list_glossaries");
+}
+
+/**
+ * Create or update a glossary (matched by name)
+ * @param glossary - Glossary to save (terms are trimmed and de-duplicated)
+ * @returns The saved glossary
+ */
+export async function saveGlossary(glossary: Glossary): Promise<Glossary> {
+  return invokeCommand<Glossary>("save_glossary", { glossary });
+}
+
+/**
+ * Delete a glossary
+ * @param name - Glossary name
+ * @returns false if the glossary didn't exist
+ */
+export async function deleteGlossary(name: string): Promise<boolean> {
+  return invokeCommand<boolean>("delete_glossary", { name });
+}
@@ -0,0 +1,112 @@
+import { invokeCommand } from "./client";
+import type { TranscribeAdvancedResponse } from "./endpoints/transcription";
+
+export type SubtitleSegment = TranscribeAdvancedResponse["segments"][number];
+
+export type ExportFormat = "srt" | "vtt" | "ass" | "txt";
+
+// Only the requested formats are present
+export type RegeneratedSubtitles = {
+  subtitles_srt?: string;
+  subtitles_vtt?: string;
+  subtitles_ass?: string;
+  text?: string;
+};
+
+export type HistoryEntrySummary = {
+  result_id: string;
+  file_path: string;
+  model_name: string;
+  language: string;
+  segment_count: number;
+  created_at_ms: number;
+  updated_at_ms: number;
+};
+
+/**
+ * Edit the text and/or timing of a segment of a saved result
+ * (timestamps must stay within the neighbouring segments)
+ * @param resultId - `result_id` returned by the transcription
+ * @param index - Segment index
+ * @returns Every segment of the result after the edit
+ */
+export async function updateSegment(
+  resultId: string,
+  index: number,
+  newText?: string,
+  newStart?: number,
+  newEnd?: number
+): Promise<SubtitleSegment[]> {
+  return invokeCommand<SubtitleSegment[]>("update_segment", {
+    resultId,
+    index,
+    newText,
+    newStart,
+    newEnd,
+  });
+}
+
+/**
+ * Merge segments firstIndex..lastIndex (inclusive) into one
+ * @returns Every segment of the result after the merge
+ */
+export async function mergeSegments(
+  resultId: string,
+  firstIndex: number,
+  lastIndex: number
+): Promise<SubtitleSegment[]> {
+  return invokeCommand<SubtitleSegment[]>("merge_segments", {
+    resultId,
+    firstIndex,
+    lastIndex,
+  });
+}
+
+/**
+ * Split a segment in two
+ * @param splitTime - Where the second part starts (seconds, inside the segment)
+ * @param textPosition - Character index where the second part's text starts
+ * @returns Every segment of the result after the split
+ */
+export async function splitSegment(
+  resultId: string,
+  index: number,
+  splitTime: number,
+  textPosition: number
+): Promise<SubtitleSegment[]> {
+  return invokeCommand<SubtitleSegment[]>("split_segment", {
+    resultId,
+    index,
+    splitTime,
+    textPosition,
+  });
+}
+
+/**
+ * Regenerate subtitles from the edited segments of a saved result
+ * @param formats - Formats to generate (e.g. ["srt", "vtt"])
+ */
+export async function regenerateSubtitles(
+  resultId: string,
+  formats: ExportFormat[]
+): Promise<RegeneratedSubtitles> {
+  return invokeCommand<RegeneratedSubtitles>("regenerate_subtitles", {
+    resultId,
+    formats,
+  });
+}
+
+/**
+ * Saved results, most recent first (the oldest ones are deleted automatically)
+ */
+export async function listHistory(): Promise<HistoryEntrySummary[]> {
+  return invokeCommand<HistoryEntrySummary[]>("list_history");
+}
+
+/**
+ * Delete a saved result, its segments can't be edited afterwards
+ * @param resultId - `result_id` returned by the transcription
+ */
+export async function deleteHistoryEntry(resultId: string): Promise<void> {
+  return invokeCommand<void>("delete_history_entry", { resultId });
+}
@@ -0,0 +1,55 @@
+import { invokeCommand } from "./client";
+import type { TranscriptionProgress } from "./endpoints/transcription";
+
+export type JobStatus = "queued" | "running" | "completed" | "failed";
+
+export type JobInfo = {
+  job_id: string;
+  kind: "transcription" | "range";
+  file_path: string;
+  model_name: string;
+  status: JobStatus;
+  last_progress: TranscriptionProgress | null;
+  error: string | null;
+  created_at_ms: number;
+  started_at_ms: number | null;
+  finished_at_ms: number | null;
+};
+
+/**
+ * Progress event name of a job (events are namespaced per job)
+ * @param jobId - Job id passed to (or generated by) the transcription command
+ */
+export function jobProgressEvent(jobId: string): string {
+  return `transcription-progress:${jobId}`;
+}
+
+/**
+ * List queued, running and recently finished transcription jobs (oldest first)
+ */
+export async function listJobs(): Promise<JobInfo[]> {
+  return invokeCommand<JobInfo[]>("list_jobs");
+}
+
+/**
+ * Get the current status of a single job
+ * @param jobId - Job id
+ */
+export async function getJobStatus(jobId: string): Promise<JobInfo> {
+  return invokeCommand<JobInfo>("get_job_status", { jobId });
+}
+
+/**
+ * Change how many transcription jobs may run at the same time
+ * @param maxJobs - Maximum number of running jobs (others are queued)
+ */
+export async function setMaxConcurrentJobs(maxJobs: number): Promise<void> {
+  return invokeCommand<void>("set_max_concurrent_jobs", { maxJobs });
+}
+
+/**
+ * Get how many transcription jobs may run at the same time
+ */
+export async function getMaxConcurrentJobs(): Promise<number> {
+  return invokeCommand<number>("get_max_concurrent_jobs");
+}
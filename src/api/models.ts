@@ -1,4 +1,5 @@
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import type { WhisperModelName } from "@constants/whisper-models";
 
 export type ModelName = WhisperModelName;
@@ -15,8 +16,11 @@ export async function listDownloadedModels(): Promise<string[]> {
   return invoke<string[]>("list_downloaded_models");
 }
 
-export async function testWhisper(modelName: ModelName): Promise<string> {
-  return invoke<string>("test_whisper", { modelName });
+export async function testWhisper(
+  modelName: ModelName,
+  ignoreMemoryCheck: boolean = false
+): Promise<string> {
+  return invoke<string>("test_whisper", { modelName, ignoreMemoryCheck });
 }
 
 export async function helloWorld(): Promise<string> {
@@ -39,3 +43,84 @@ export async function downloadVoskModel(modelName: string): Promise<string> {
 export async function listVoskModels(): Promise<string[]> {
   return invoke<string[]>("list_vosk_models");
 }
+
+export type ModelRequirements = {
+  model_name: string;
+  model_size_mb: number;
+  estimated_ram_mb: number;
+  available_ram_mb: number;
+  total_ram_mb: number;
+  fits: boolean; // false = loading the model will most likely crash the app
+};
+
+/**
+ * Compare a downloaded model's estimated RAM usage with the available memory
+ * @param modelName - Whisper model name (e.g. "large-v3")
+ */
+export async function checkModelRequirements(
+  modelName: ModelName
+): Promise<ModelRequirements> {
+  return invoke<ModelRequirements>("check_model_requirements", { modelName });
+}
+
+export type BenchmarkResult = {
+  model_name: string;
+  load_time_ms: number;
+  transcription_time_ms: number;
+  audio_duration_sec: number;
+  realtime_factor: number; // < 1.0 = faster than realtime
+  peak_memory_mb: number;
+  thread_count: number;
+};
+
+export type BenchmarkProgress =
+  | { type: "started"; model_name: string; index: number; total: number }
+  | { type: "completed"; index: number; total: number; result: BenchmarkResult }
+  | {
+      type: "failed";
+      model_name: string;
+      index: number;
+      total: number;
+      error: string;
+    };
+
+/**
+ * Benchmark a downloaded Whisper model on this machine
+ * @param modelName - Whisper model name (e.g. "small")
+ * @param ignoreMemoryCheck - Load the model even if it likely doesn't fit in memory
+ * @returns Load time, realtime factor, peak memory and thread count
+ */
+export async function benchmarkModel(
+  modelName: ModelName,
+  ignoreMemoryCheck: boolean = false
+): Promise<BenchmarkResult> {
+  return invoke<BenchmarkResult>("benchmark_model", {
+    modelName,
+    ignoreMemoryCheck,
+  });
+}
+
+/**
+ * Benchmark every downloaded Whisper model sequentially
+ * @param onProgress - Callback for per-model progress updates
+ * @param ignoreMemoryCheck - Also run models that likely don't fit in memory
+ * @returns Results for every model that ran successfully
+ */
+export async function benchmarkAllInstalled(
+  onProgress?: (progress: BenchmarkProgress) => void,
+  ignoreMemoryCheck: boolean = false
+): Promise<BenchmarkResult[]> {
+  const unlisten = onProgress
+    ? await listen<BenchmarkProgress>("benchmark-progress", (event) =>
+        onProgress(event.payload)
+      )
+    : undefined;
+
+  try {
+    return await invoke<BenchmarkResult[]>("benchmark_all_installed", {
+      ignoreMemoryCheck,
+    });
+  } finally {
+    unlisten?.();
+  }
+}
@@ -0,0 +1,92 @@
+import { invokeCommand } from "./client";
+
+export type ApiServerInfo = {
+  port: number;
+  token: string;
+  base_url: string; // e.g. "http://127.0.0.1:8178/v1"
+  default_model: string;
+};
+
+/**
+ * Start the local OpenAI-compatible API server (POST /v1/audio/transcriptions)
+ * @param port - Port to bind on 127.0.0.1 (defaults to 8178, 0 = any free port)
+ * @param token - Bearer token clients must send (random when omitted)
+ * @param defaultModel - Whisper model used for "whisper-1" requests (defaults to "base")
+ * @returns Running server info (including the token to share with clients)
+ */
+export async function startApiServer(
+  port?: number,
+  token?: string,
+  defaultModel?: string
+): Promise<ApiServerInfo> {
+  return invokeCommand<ApiServerInfo>("start_api_server", {
+    port,
+    token,
+    defaultModel,
+  });
+}
+
+/**
+ * Stop the local API server
+ * @returns false if the server wasn't running
+ */
+export async function stopApiServer(): Promise<boolean> {
+  return invokeCommand<boolean>("stop_api_server");
+}
+
+/**
+ * Get the running API server info
+ * @returns Server info, or null when stopped
+ */
+export async function getApiServerStatus(): Promise<ApiServerInfo | null> {
+  return invokeCommand<ApiServerInfo | null>("get_api_server_status");
+}
+
+export type CaptionServerInfo = {
+  port: number;
+  token: string; // New on every start, required by the WebSocket (already in both URLs)
+  browser_source_url: string; // Add as an OBS browser source
+  websocket_url: string;
+  published_session_id: string | null; // null = every live session
+};
+
+/**
+ * Start the live caption server (browser source page + WebSocket feed)
+ * @param port - Port to bind on 127.0.0.1 (defaults to 8179)
+ * @param sessionId - Live session to publish (omit to publish every session)
+ */
+export async function startCaptionServer(
+  port?: number,
+  sessionId?: string
+): Promise<CaptionServerInfo> {
+  return invokeCommand<CaptionServerInfo>("start_caption_server", {
+    port,
+    sessionId,
+  });
+}
+
+/**
+ * Stop the live caption server
+ * @returns false if the server wasn't running
+ */
+export async function stopCaptionServer(): Promise<boolean> {
+  return invokeCommand<boolean>("stop_caption_server");
+}
+
+/**
+ * Choose which live session is published to caption clients
+ * @param sessionId - Session ID (Vosk session or "whisper-live"), null = every session
+ */
+export async function setCaptionSession(
+  sessionId: string | null
+): Promise<void> {
+  return invokeCommand<void>("set_caption_session", { sessionId });
+}
+
+/**
+ * Get the running caption server info
+ * @returns Server info, or null when stopped
+ */
+export async function getCaptionServerStatus(): Promise<CaptionServerInfo | null> {
+  return invokeCommand<CaptionServerInfo | null>("get_caption_server_status");
+}
@@ -14,3 +14,32 @@ export type GpuInfo = {
 export async function getGpuInfo(): Promise<GpuInfo> {
   return invokeCommand<GpuInfo>("get_gpu_info");
 }
+
+export type LogLevel = "error" | "warn" | "info" | "debug" | "trace";
+
+export type LogEntry = {
+  timestamp_ms: number;
+  level: "ERROR" | "WARN" | "INFO" | "DEBUG" | "TRACE";
+  target: string;
+  message: string;
+};
+
+/**
+ * Get the most recent backend logs (oldest first)
+ * @param level - Minimum severity to include (defaults to all)
+ * @param limit - Maximum number of entries (defaults to 200)
+ */
+export async function getRecentLogs(
+  level?: LogLevel,
+  limit?: number
+): Promise<LogEntry[]> {
+  return invokeCommand<LogEntry[]>("get_recent_logs", { level, limit });
+}
+
+/**
+ * Change the backend log level at runtime
+ * @param level - New log level (or "off")
+ */
+export async function setLogLevel(level: LogLevel | "off"): Promise<void> {
+  return invokeCommand<void>("set_log_level", { level });
+}
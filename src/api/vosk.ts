@@ -1,28 +1,116 @@
 import { invoke } from "@tauri-apps/api/core";
 
+export interface VoskWord {
+  word: string;
+  start: number; // seconds since session start
+  end: number;
+  confidence?: number; // not reported for alternatives
+}
+
+export interface VoskAlternative {
+  text: string;
+  confidence: number;
+}
+
+export interface VoskSpeakerInfo {
+  vector: number[]; // speaker embedding, compare vectors to tell speakers apart
+  frames: number;
+}
+
 export interface VoskTranscriptionResult {
   text: string;
   is_partial: boolean;
+  utterance_id: number; // partials share the id of their final result
+  words?: VoskWord[]; // final results with `words` enabled
+  alternatives?: VoskAlternative[]; // final results with `max_alternatives` > 0
+  speaker?: VoskSpeakerInfo; // final results with a speaker model
+}
+
+export interface VoskSessionOptions {
+  speaker_model_name?: string; // e.g. "vosk-model-spk-0.4"
+  max_alternatives?: number; // 0 = best result only
+  words?: boolean; // per-word timings and confidences
+  nlsml?: boolean; // not supported yet, rejected by the backend
+  target_language?: "en"; // translate final results (Whisper's translate task, English only)
+  translation_model_name?: string; // multilingual Whisper model, defaults to the refine model then "base"
+  refine_model_name?: string; // dual-model mode: Whisper model refining final results (e.g. "base")
+  ignore_memory_check?: boolean; // load the Whisper model even if it likely doesn't fit in memory
+}
+
+export interface VoskSessionInfo {
+  session_id: string;
+  model_name: string;
+  sample_rate: number;
+  options: VoskSessionOptions;
+  started_at_ms: number;
+  last_activity_ms: number;
+  idle_seconds: number;
+  chunks_processed: number;
+}
+
+// Payload of "vosk-session-expired" events and of forceCloseAllSessions()
+export interface ClosedVoskSession {
+  session_id: string;
+  final_text: string;
+  idle_seconds: number;
+}
+
+// Payload of "vosk-utterance-refined" events (dual-model mode)
+export interface RefinedVoskUtterance {
+  session_id: string;
+  utterance_id: number; // same id as the Vosk final result
+  original_text: string; // Vosk's text
+  text: string; // Whisper's text (Vosk's text if refinement failed)
+  language: string | null;
+  error: string | null;
+}
+
+// Payload of "vosk-utterance-translated" events (sessions with a `target_language`)
+export interface TranslatedVoskUtterance {
+  session_id: string;
+  utterance_id: number; // same id as the Vosk final result
+  original_text: string; // Vosk's text
+  translated_text: string | null; // null if the translation failed
+  error: string | null;
 }
 
 /**
  * Start a new Vosk live transcription session
  * @param modelName - Vosk model name (e.g., "vosk-model-small-en-us-0.15")
  * @param sampleRate - Audio sample rate (typically 16000 Hz)
+ * @param options - Speaker model, alternatives, word timings, translation and
+ * Whisper refinement of final results (all off by default)
  * @returns Session ID for use in subsequent chunk calls
  */
 export async function startVoskSession(
   modelName: string,
-  sampleRate: number
+  sampleRate: number,
+  options?: VoskSessionOptions
 ): Promise<string> {
-  return invoke<string>("start_vosk_session", { modelName, sampleRate });
+  return invoke<string>("start_vosk_session", {
+    modelName,
+    sampleRate,
+    options: options ?? null,
+  });
+}
+
+/**
+ * Get the model and options of an active Vosk session
+ * @param sessionId - Session ID from startVoskSession()
+ */
+export async function getVoskSessionInfo(
+  sessionId: string
+): Promise<VoskSessionInfo> {
+  return invoke<VoskSessionInfo>("get_vosk_session_info", { sessionId });
 }
 
 /**
  * Process audio chunk in existing Vosk session
  * @param sessionId - Session ID from startVoskSession()
  * @param pcmAudio - PCM audio samples (signed 16-bit, mono)
- * @returns Transcription result (partial or final)
+ * @returns Transcription result (partial or final), translations and Whisper
+ * refinements of final results arrive later as "vosk-utterance-translated" and
+ * "vosk-utterance-refined" events
  */
 export async function processVoskChunk(
   sessionId: string,
@@ -42,3 +130,34 @@ export async function processVoskChunk(
 export async function endVoskSession(sessionId: string): Promise<string> {
   return invoke<string>("end_vosk_session", { sessionId });
 }
+
+/**
+ * List active Vosk sessions (with their idle time)
+ */
+export async function listVoskSessions(): Promise<VoskSessionInfo[]> {
+  return invoke<VoskSessionInfo[]>("list_vosk_sessions");
+}
+
+/**
+ * Finalize and drop every Vosk session (frees the loaded models)
+ * @returns Closed sessions with their final text
+ */
+export async function forceCloseAllSessions(): Promise<ClosedVoskSession[]> {
+  return invoke<ClosedVoskSession[]>("force_close_all_sessions");
+}
+
+/**
+ * Change how long a session may go without audio before it's closed
+ * (a "vosk-session-expired" event is emitted when that happens)
+ * @param seconds - Idle timeout in seconds (defaults to 300)
+ */
+export async function setVoskIdleTimeout(seconds: number): Promise<void> {
+  return invoke<void>("set_vosk_idle_timeout", { seconds });
+}
+
+/**
+ * Get the current idle timeout in seconds
+ */
+export async function getVoskIdleTimeout(): Promise<number> {
+  return invoke<number>("get_vosk_idle_timeout");
+}
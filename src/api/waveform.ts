@@ -0,0 +1,30 @@
+import { invokeCommand } from "./client";
+
+export type SilenceRegion = {
+  start: number; // seconds
+  end: number;
+};
+
+// peaks[i] / rms[i] cover [i / samples_per_second, (i + 1) / samples_per_second)
+export type AudioWaveform = {
+  duration_seconds: number;
+  samples_per_second: number;
+  peaks: number[]; // max absolute amplitude (0 - 1)
+  rms: number[]; // RMS amplitude (0 - 1)
+  silences: SilenceRegion[]; // below -40 dBFS for at least 0.5s
+};
+
+/**
+ * Decode a file and get its downsampled waveform (computed in the backend)
+ * @param filePath - Audio or video file
+ * @param samplesPerSecond - Waveform resolution (1 - 1000)
+ */
+export async function getAudioWaveform(
+  filePath: string,
+  samplesPerSecond: number
+): Promise<AudioWaveform> {
+  return invokeCommand<AudioWaveform>("get_audio_waveform", {
+    filePath,
+    samplesPerSecond,
+  });
+}
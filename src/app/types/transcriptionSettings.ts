@@ -21,6 +21,33 @@ export type BeamSearchSettings = {
 
 export type SamplingStrategy = GreedySettings | BeamSearchSettings;
 
+// Hallucination filtering (all disabled when omitted)
+export type SegmentFilterSettings = {
+  min_avg_probability?: number | null; // Drop segments below this confidence (0.0 - 1.0)
+  max_no_speech_probability?: number | null; // Drop segments likely to be silence (0.0 - 1.0)
+  drop_repeated_segments: boolean; // Drop segments repeating the previous one
+};
+
+// Audio preprocessing applied during ffmpeg conversion (all disabled when omitted)
+export type AudioPreprocessingSettings = {
+  highpass_hz?: number | null; // Cut rumble below this frequency (e.g. 80)
+  noise_reduction: boolean; // FFT-based noise reduction (afftdn)
+  loudness_normalization: boolean; // EBU R128 normalization (loudnorm), for quiet recordings
+};
+
+// Re-decode failing segments at higher temperatures (disabled when omitted)
+export type TemperatureFallbackSettings = {
+  schedule: number[]; // Temperatures tried in order, e.g. [0.2, 0.4, 0.6, 0.8, 1.0]
+  compression_ratio_threshold?: number | null; // Retry repetitive text (e.g. 2.4)
+  logprob_threshold?: number | null; // Retry unlikely text (e.g. -1.0)
+};
+
+// Saved glossaries to use (see src/api/glossary.ts)
+export type GlossarySettings = {
+  names: string[]; // Glossary names, terms are added to the initial prompt
+  apply_replacements: boolean; // Also apply their find/replace corrections
+};
+
 // Quality preset types
 export type QualityPreset = "fast" | "balanced" | "best" | "custom";
 
@@ -30,8 +57,10 @@ export type TranscriptionSettings = {
   preset: QualityPreset;
 
   // Core settings
+  language?: string | null; // Spoken language code (e.g. "fr"), skips language detection
   sampling_strategy: SamplingStrategy;
   temperature: number; // 0.0 - 1.0
+  temperature_fallback?: TemperatureFallbackSettings | null; // Fallback ladder for failing segments
 
   // Performance
   thread_count: number | "auto"; // Number of CPU threads or "auto"
@@ -39,9 +68,16 @@ export type TranscriptionSettings = {
   // Advanced settings
   no_context: boolean; // Don't use previous text as context
   initial_prompt: string | null; // Context/vocabulary hint
+  glossary?: GlossarySettings | null; // Managed term lists (composed into the prompt)
   max_text_context: number | null; // Max tokens from past text
   entropy_threshold: number | null; // Reject low-confidence segments
   no_speech_threshold: number | null; // Silence detection sensitivity
+
+  // Post-processing
+  filtering?: SegmentFilterSettings | null; // Hallucination filtering
+
+  // Pre-processing
+  preprocessing?: AudioPreprocessingSettings | null; // ffmpeg filters before transcription
 };
 
 // Default preset configurations
@@ -136,16 +172,21 @@ function isSettingsEqual(a: TranscriptionSettings, b: TranscriptionSettings): bo
   const keysToCompare: Array<keyof TranscriptionSettings> = [
     'sampling_strategy',
     'temperature',
+    'temperature_fallback',
     'no_context',
     'initial_prompt',
+    'glossary',
     'max_text_context',
     'entropy_threshold',
     'no_speech_threshold',
+    'filtering',
+    'preprocessing',
   ];
 
   return keysToCompare.every((key) => {
-    const valueA = a[key];
-    const valueB = b[key];
+    // Optional settings can be missing or null, both mean "disabled"
+    const valueA = a[key] ?? null;
+    const valueB = b[key] ?? null;
 
     // Deep comparison for objects (sampling_strategy)
     if (typeof valueA === 'object' && typeof valueB === 'object') {